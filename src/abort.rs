@@ -0,0 +1,63 @@
+use futures::channel::oneshot::{self, Receiver, Sender};
+use std::{error::Error, fmt};
+
+/// Handle returned by `Streamline::abortable` that can be used to give up on an in-progress
+/// reversion. Aborting is a last resort: any states that have not been reverted yet are left
+/// un-compensated, so their side effects will need to be cleaned up some other way.
+pub struct AbortRevert {
+    sender: Sender<()>,
+}
+
+impl AbortRevert {
+    /// Stop reverting the associated `Streamline` before its next revert step, ending it with a
+    /// `RevertProgress::Failure` whose error is converted from `RevertAborted`. If the
+    /// `Streamline` is not reverting yet, the abort takes effect once reversion starts.
+    #[allow(clippy::result_unit_err)]
+    pub fn abort_revert(self) -> Result<(), ()> {
+        self.sender.send(())
+    }
+}
+
+/// The error used to end a reversion that was aborted through `AbortRevert`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RevertAborted;
+
+impl fmt::Display for RevertAborted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("reversion aborted")
+    }
+}
+
+impl Error for RevertAborted {}
+
+/// Create a connected `AbortRevert` handle and `Abort` source for an abortable `Streamline`
+pub(crate) fn channel<E>() -> (AbortRevert, Abort<E>)
+where
+    E: From<RevertAborted>,
+{
+    let (sender, receiver) = oneshot::channel();
+
+    let abort = Abort {
+        receiver,
+        error: || E::from(RevertAborted),
+    };
+
+    (AbortRevert { sender }, abort)
+}
+
+/// The receiving end of an `AbortRevert` handle, polled by a `Streamline` before each revert step
+pub(crate) struct Abort<E> {
+    receiver: Receiver<()>,
+    error: fn() -> E,
+}
+
+impl<E> Abort<E> {
+    /// Check (without waiting) whether the reversion has been aborted, returning the error to
+    /// end the reversion with if it has
+    pub(crate) fn aborted(&mut self) -> Option<E> {
+        match self.receiver.try_recv() {
+            Ok(Some(())) => Some((self.error)()),
+            _ => None,
+        }
+    }
+}
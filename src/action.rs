@@ -0,0 +1,110 @@
+use crate::{state::State, streamline::Streamline};
+use async_trait::async_trait;
+use futures::future::LocalBoxFuture;
+use std::{cell::Cell, fmt, rc::Rc};
+
+/// An async closure run with the (optional) context by an `Action`, shared between the clones
+/// of the `Action` so that it can be run again (e.g. when retrying)
+type ActionFn<C, E> = Rc<dyn for<'a> Fn(Option<&'a mut C>) -> LocalBoxFuture<'a, Result<(), E>>>;
+
+/// The minimal two-state machine synthesized by `Streamline::from_action`, which runs a single
+/// fallible action and compensates for it on failure (or cancellation). Progress is reported in
+/// the same shape as any hand-written machine: `Progress::Ok` for the pending action, then for the
+/// completed one, or reversion progress if the action fails.
+///
+/// Two `Action`s are equal if they are in the same state of the same machine, i.e. if they share
+/// the same action and compensation closures.
+pub struct Action<C, E> {
+    done: bool,
+    // whether the action has been run at all, shared between the clones of the `Action`
+    ran: Rc<Cell<bool>>,
+    action: ActionFn<C, E>,
+    compensate: ActionFn<C, E>,
+}
+
+impl<C, E> Action<C, E> {
+    /// Whether the action has completed successfully
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+}
+
+impl<C, E> Clone for Action<C, E> {
+    fn clone(&self) -> Self {
+        Self {
+            done: self.done,
+            ran: self.ran.clone(),
+            action: self.action.clone(),
+            compensate: self.compensate.clone(),
+        }
+    }
+}
+
+impl<C, E> PartialEq for Action<C, E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.done == other.done
+            && Rc::ptr_eq(&self.action, &other.action)
+            && Rc::ptr_eq(&self.compensate, &other.compensate)
+    }
+}
+
+impl<C, E> fmt::Debug for Action<C, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Action").field("done", &self.done).finish()
+    }
+}
+
+#[async_trait(?Send)]
+impl<C, E> State for Action<C, E> {
+    type Context = C;
+    type Error = E;
+
+    async fn next(&self, context: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+        if self.done {
+            return Ok(None);
+        }
+
+        self.ran.set(true);
+
+        (self.action)(context).await?;
+
+        Ok(Some(Self {
+            done: true,
+            ..self.clone()
+        }))
+    }
+
+    async fn revert(
+        &self,
+        context: Option<&mut Self::Context>,
+    ) -> Result<Option<Self>, Self::Error> {
+        // there is nothing to compensate for if the action never ran (e.g. after a cancellation)
+        if self.ran.get() {
+            (self.compensate)(context).await?;
+        }
+
+        Ok(None)
+    }
+}
+
+impl<C, E> Streamline<C, E, Action<C, E>> {
+    /// Build a `Streamline` from a single fallible action and its compensation, for simple cases
+    /// without a natural state `enum`. The action is run on the first transition, and again on
+    /// every retry of that transition (e.g. under `Streamline::retry`). If it fails for good (or
+    /// the `Streamline` is cancelled after it ran), `compensate` is run during reversion, and
+    /// again on every retry of the reversion. `compensate` is skipped if the `Streamline` is
+    /// cancelled before the action ever ran. Both closures receive the (optional) context of the
+    /// `Streamline`.
+    pub fn from_action<A, R>(action: A, compensate: R) -> Self
+    where
+        A: for<'a> Fn(Option<&'a mut C>) -> LocalBoxFuture<'a, Result<(), E>> + 'static,
+        R: for<'a> Fn(Option<&'a mut C>) -> LocalBoxFuture<'a, Result<(), E>> + 'static,
+    {
+        Self::build(Action {
+            done: false,
+            ran: Rc::new(Cell::new(false)),
+            action: Rc::new(action),
+            compensate: Rc::new(compensate),
+        })
+    }
+}
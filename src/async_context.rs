@@ -0,0 +1,39 @@
+use std::sync::Arc;
+use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// A shareable `Context` wrapper backed by an async-aware `RwLock`. Unlike a `std::sync::Mutex`,
+/// its guards can safely be held across `.await` points inside of `State::next` and
+/// `State::revert` without blocking the executor, even when the same context is shared between
+/// several concurrently-running `Streamline`s. Cloning an `AsyncContext` shares the same inner
+/// value.
+#[derive(Debug, Default)]
+pub struct AsyncContext<T> {
+    inner: Arc<RwLock<T>>,
+}
+
+impl<T> Clone for AsyncContext<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> AsyncContext<T> {
+    /// Wrap a value in an `AsyncContext`
+    pub fn new(value: T) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(value)),
+        }
+    }
+
+    /// Wait for shared read access to the inner value
+    pub async fn read(&self) -> RwLockReadGuard<'_, T> {
+        self.inner.read().await
+    }
+
+    /// Wait for exclusive write access to the inner value
+    pub async fn write(&self) -> RwLockWriteGuard<'_, T> {
+        self.inner.write().await
+    }
+}
@@ -0,0 +1,162 @@
+#[cfg(feature = "revert")]
+use crate::state::State;
+#[cfg(feature = "revert")]
+use async_trait::async_trait;
+#[cfg(feature = "revert")]
+use futures::{future::LocalBoxFuture, FutureExt};
+use futures::{stream, StreamExt};
+use std::future::Future;
+
+/// A `State` whose transitions may run over a batch of items (e.g. to create several resources),
+/// with per-item rollback: once enabled through `Streamline::batched`, the `Streamline` runs
+/// every item of a batch through `next_item`, and a batch that fails part of the way through has
+/// only the items that succeeded compensated through `revert_items`, as the first step of
+/// reverting the failed state. Only available with the `revert` feature.
+///
+/// This is a separate trait rather than a set of default methods on `State`, since items and
+/// handles need their own types: associated types can't have defaults on stable Rust, and adding
+/// required ones to `State` would break every existing implementation.
+#[cfg(feature = "revert")]
+#[async_trait(?Send)]
+pub trait BatchState: State {
+    /// An item of a batch, processed by `next_item`
+    type Item;
+    /// A handle to an item of a batch that was processed successfully, used to compensate it
+    type Handle;
+
+    /// The items that this state's transition runs over, in place of `next`. By default, states
+    /// are not batches, returning `None` to run `next` as usual.
+    fn batch(&self) -> Option<Vec<Self::Item>> {
+        None
+    }
+
+    /// Processes a single item of this state's batch, returning a handle to what it produced.
+    /// Items only get shared access to the context, since they may run concurrently (see
+    /// `Streamline::max_parallelism`).
+    async fn next_item(
+        &self,
+        item: Self::Item,
+        context: Option<&Self::Context>,
+    ) -> Result<Self::Handle, Self::Error>;
+
+    /// Derives the next state from the handles of a batch whose every item succeeded, like the
+    /// `Ok` value of `next`
+    fn batch_completed(&self, handles: Vec<Self::Handle>) -> Option<Self>;
+
+    /// Compensates the items of a failed batch that succeeded before the error, before this
+    /// state's own `revert` runs. Errors end the reversion like errors from `revert`.
+    async fn revert_items(
+        &self,
+        handles: Vec<Self::Handle>,
+        context: Option<&mut Self::Context>,
+    ) -> Result<(), Self::Error>;
+}
+
+/// The compensation of the items of a failed batch that succeeded, run once reversion starts
+#[cfg(feature = "revert")]
+pub(crate) type PartialBatch<C, E> =
+    Box<dyn for<'a> FnOnce(Option<&'a mut C>) -> LocalBoxFuture<'a, Result<(), E>>>;
+
+/// The outcome of a batch: either the next state, or the error that failed it along with the
+/// compensation of the items that succeeded before it
+#[cfg(feature = "revert")]
+pub(crate) type BatchResult<S, C, E> = Result<Option<S>, (E, PartialBatch<C, E>)>;
+
+/// The `BatchState` implementation of a state, captured as a function pointer
+#[cfg(feature = "revert")]
+pub(crate) type BatchRunner<S, C, E> =
+    for<'a> fn(&'a S, usize, Option<&'a C>) -> LocalBoxFuture<'a, Option<BatchResult<S, C, E>>>;
+
+/// Run the items of a state's batch with at most `max_parallelism` of them in flight at once,
+/// deferring the compensation of a failed batch's partial handles
+#[cfg(feature = "revert")]
+pub(crate) fn run_batch<'a, S, C, E>(
+    state: &'a S,
+    max_parallelism: usize,
+    context: Option<&'a C>,
+) -> LocalBoxFuture<'a, Option<BatchResult<S, C, E>>>
+where
+    S: BatchState<Context = C, Error = E> + 'static,
+    S::Handle: 'static,
+    E: 'static,
+{
+    async move {
+        let items = state.batch()?;
+
+        let next_item = |item| state.next_item(item, context);
+
+        match next_batch_parallel(items, max_parallelism, next_item).await {
+            Ok(handles) => Some(Ok(state.batch_completed(handles))),
+            Err((error, handles)) => {
+                let state = state.clone();
+
+                let compensate: PartialBatch<C, E> = Box::new(move |context| {
+                    async move { state.revert_items(handles, context).await }.boxed_local()
+                });
+
+                Some(Err((error, compensate)))
+            }
+        }
+    }
+    .boxed_local()
+}
+
+/// Run a fallible operation over every item of a batch in order, for use within a single state's
+/// `next` (e.g. to create several resources). Returns the handles produced for every item, or
+/// the first error along with the handles of the items that succeeded before it, so that only
+/// those items need to be compensated.
+pub async fn next_batch<I, F, Fut, H, E>(items: I, mut f: F) -> Result<Vec<H>, (E, Vec<H>)>
+where
+    I: IntoIterator,
+    F: FnMut(I::Item) -> Fut,
+    Fut: Future<Output = Result<H, E>>,
+{
+    let mut handles = vec![];
+
+    for item in items {
+        match f(item).await {
+            Ok(handle) => handles.push(handle),
+            Err(error) => return Err((error, handles)),
+        }
+    }
+
+    Ok(handles)
+}
+
+/// Run a fallible operation over every item of a batch concurrently (like `next_batch`), with at
+/// most `max_parallelism` operations in flight at once: items beyond the cap are queued until a
+/// slot frees up, which bounds resource usage when the size of the batch is data-dependent.
+/// Handles are returned in completion order. On the first error, operations that are still in
+/// flight are dropped, and only the handles of the items that had already succeeded are returned.
+///
+/// All operations run within the same transition, so a semaphore set through
+/// `Streamline::with_semaphore` is only acquired once for the whole batch: the two limits compose
+/// as "at most `max_parallelism` operations within each of the permitted transitions".
+///
+/// # Panics
+///
+/// Panics if `max_parallelism` is zero.
+pub async fn next_batch_parallel<I, F, Fut, H, E>(
+    items: I,
+    max_parallelism: usize,
+    f: F,
+) -> Result<Vec<H>, (E, Vec<H>)>
+where
+    I: IntoIterator,
+    F: FnMut(I::Item) -> Fut,
+    Fut: Future<Output = Result<H, E>>,
+{
+    assert!(max_parallelism > 0, "max_parallelism must be at least 1");
+
+    let mut results = stream::iter(items).map(f).buffer_unordered(max_parallelism);
+    let mut handles = vec![];
+
+    while let Some(result) = results.next().await {
+        match result {
+            Ok(handle) => handles.push(handle),
+            Err(error) => return Err((error, handles)),
+        }
+    }
+
+    Ok(handles)
+}
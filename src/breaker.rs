@@ -0,0 +1,142 @@
+use std::{
+    error::Error,
+    fmt,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// A circuit breaker shared between many runs of the same machine (e.g. one per request) through
+/// `Streamline::with_breaker`, which stops attempting new runs once `threshold` runs in a row
+/// have failed. Runs that revert or fail count as failures, and runs that complete reset the
+/// count. Cloning a `CircuitBreaker` shares the same count.
+#[derive(Clone, Debug)]
+pub struct CircuitBreaker {
+    threshold: u32,
+    cooldown: Option<Duration>,
+    state: Arc<Mutex<BreakerState>>,
+}
+
+#[derive(Debug, Default)]
+struct BreakerState {
+    failures: u32,
+    tripped_at: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    /// Create a breaker that opens after `threshold` consecutive failed runs, and stays open
+    /// until it is `reset`
+    pub fn new(threshold: u32) -> Self {
+        Self {
+            threshold,
+            cooldown: None,
+            state: Arc::default(),
+        }
+    }
+
+    /// Let a single trial run through once the breaker has been open for `cooldown`. If the
+    /// trial run completes, the breaker closes again, and if it fails, the breaker re-opens for
+    /// another `cooldown`.
+    pub fn cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = Some(cooldown);
+        self
+    }
+
+    /// Check whether new runs are currently being rejected
+    pub fn is_open(&self) -> bool {
+        let state = self.state.lock().expect("circuit breaker lock poisoned");
+
+        match (state.tripped_at, self.cooldown) {
+            (None, _) => false,
+            (Some(tripped_at), Some(cooldown)) => tripped_at.elapsed() < cooldown,
+            (Some(_), None) => true,
+        }
+    }
+
+    /// The number of consecutive failed runs
+    pub fn failures(&self) -> u32 {
+        self.state
+            .lock()
+            .expect("circuit breaker lock poisoned")
+            .failures
+    }
+
+    /// Close the breaker, clearing the failure count
+    pub fn reset(&self) {
+        *self.state.lock().expect("circuit breaker lock poisoned") = BreakerState::default();
+    }
+
+    /// Record the end of a run
+    fn record(&self, succeeded: bool) {
+        if succeeded {
+            return self.reset();
+        }
+
+        let mut state = self.state.lock().expect("circuit breaker lock poisoned");
+
+        state.failures = state.failures.saturating_add(1);
+
+        if state.failures >= self.threshold {
+            state.tripped_at = Some(Instant::now());
+        }
+    }
+}
+
+/// The error used to end a `Streamline` rejected by an open `CircuitBreaker`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CircuitOpen;
+
+impl fmt::Display for CircuitOpen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("circuit breaker is open")
+    }
+}
+
+impl Error for CircuitOpen {}
+
+/// A `CircuitBreaker` attached to a single run of a `Streamline`
+pub(crate) struct Breaker<E> {
+    breaker: CircuitBreaker,
+    error: fn() -> E,
+    admitted: Option<bool>,
+}
+
+impl<E> Breaker<E>
+where
+    E: From<CircuitOpen>,
+{
+    /// Attach a `CircuitBreaker` to a run
+    pub(crate) fn new(breaker: CircuitBreaker) -> Self {
+        Self {
+            breaker,
+            error: || E::from(CircuitOpen),
+            admitted: None,
+        }
+    }
+}
+
+impl<E> Breaker<E> {
+    /// Check (only once per run) whether the run is allowed to start, returning the error to end
+    /// it with if it isn't
+    pub(crate) fn rejected(&mut self) -> Option<E> {
+        if self.admitted.is_some() {
+            return None;
+        }
+
+        let admitted = !self.breaker.is_open();
+
+        self.admitted = Some(admitted);
+
+        if admitted {
+            None
+        } else {
+            Some((self.error)())
+        }
+    }
+
+    /// Record the end of an admitted run
+    pub(crate) fn record(&self, succeeded: bool) {
+        if self.admitted == Some(true) {
+            self.breaker.record(succeeded);
+        }
+    }
+}
@@ -1,20 +1,317 @@
-use futures::channel::oneshot::Sender;
+use futures::{
+    channel::oneshot::{self, Receiver, Sender},
+    future::{self, Either},
+    FutureExt,
+};
+use futures_timer::Delay;
+use std::{
+    cell::RefCell,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+    time::Duration,
+};
 
 /// Cancellation handle returned by `run_preemptible` that can be used to trigger `Streamline`
-/// revert processes from outside the `next` method
-pub struct Cancel {
-    sender: Sender<()>,
+/// revert processes from outside the `next` method. Handles returned by
+/// `run_preemptible_with_reason` carry a typed reason `R` along with the cancellation.
+pub struct Cancel<R = ()> {
+    sender: Sender<R>,
+    acknowledgement: Option<Receiver<()>>,
 }
 
-impl From<Sender<()>> for Cancel {
-    fn from(sender: Sender<()>) -> Self {
-        Self { sender }
+impl<R> From<Sender<R>> for Cancel<R> {
+    fn from(sender: Sender<R>) -> Self {
+        Self {
+            sender,
+            acknowledgement: None,
+        }
+    }
+}
+
+impl<R> Cancel<R> {
+    /// Cancel the associated `Streamline` for the given reason, which the `Streamline` reports
+    /// through `RevertProgress::Cancelled` (or `Progress::Cancelled`, without the `revert`
+    /// feature). Returns the reason if the `Streamline` has already finished (or been dropped).
+    pub fn cancel_with_reason(self, reason: R) -> Result<(), R> {
+        self.sender.send(reason)
+    }
+
+    /// Cancel the associated `Streamline` for the given reason, returning a `Future` that
+    /// resolves once the `Streamline` has observed the cancellation and started reverting. The
+    /// `Future` also resolves if the `Streamline` finishes (or is dropped) without observing the
+    /// cancellation, so the `Streamline`'s stream must be polled concurrently for the
+    /// acknowledgement to arrive.
+    pub fn cancel_with_reason_and_wait(self, reason: R) -> impl Future<Output = ()> {
+        let acknowledgement = match self.sender.send(reason) {
+            Ok(()) => self.acknowledgement,
+            Err(_) => None,
+        };
+
+        async move {
+            if let Some(acknowledgement) = acknowledgement {
+                acknowledgement.await.ok();
+            }
+        }
     }
 }
 
 impl Cancel {
     /// Cancellation method for cancelling a `Streamline` associated with a parent `Cancel`
+    #[allow(clippy::result_unit_err)]
     pub fn cancel(self) -> Result<(), ()> {
         self.sender.send(())
     }
+
+    /// Cancel the associated `Streamline`, returning a `Future` that resolves once the
+    /// `Streamline` has observed the cancellation and started reverting (see
+    /// `cancel_with_reason_and_wait`)
+    pub fn cancel_and_wait(self) -> impl Future<Output = ()> {
+        self.cancel_with_reason_and_wait(())
+    }
+}
+
+/// Cancellation handle returned by `Streamline::cancel_debounced`, backed by a flag that can be
+/// cleared again with `uncancel` during the debounce delay. Handles can be cloned to share the
+/// same flag between several signal sources.
+#[derive(Clone, Debug, Default)]
+pub struct DebouncedCancel {
+    flag: Arc<AtomicBool>,
+}
+
+impl DebouncedCancel {
+    /// Request cancellation of the associated `Streamline`
+    pub fn cancel(&self) {
+        self.flag.store(true, Ordering::SeqCst);
+    }
+
+    /// Withdraw an earlier cancellation request. This only has an effect if the `Streamline` has
+    /// not yet committed to reverting.
+    pub fn uncancel(&self) {
+        self.flag.store(false, Ordering::SeqCst);
+    }
+
+    /// Check whether cancellation is currently requested
+    pub fn is_cancelled(&self) -> bool {
+        self.flag.load(Ordering::SeqCst)
+    }
+}
+
+/// The receiving end of the cancellation source of a `Streamline` (e.g. of a `Cancel` or
+/// `DebouncedCancel` handle), as detached through `Streamline::detach_cancel`. Cancellation
+/// plumbing can't be serialized, so it is kept apart while the rest of the `Streamline` is, to
+/// be attached again with `Streamline::attach_cancel`. Requests made in the meantime are not
+/// lost: they are observed once the receiver is attached again.
+pub struct CancelReceiver(pub(crate) Cancellation);
+
+/// Create a connected `Cancel` handle and `Cancellation` source for a preemptible `Streamline`
+pub(crate) fn channel() -> (Cancel, Cancellation) {
+    let (sender, receiver) = oneshot::channel();
+    let (acknowledge, acknowledgement) = oneshot::channel();
+
+    let cancel = Cancel {
+        sender,
+        acknowledgement: Some(acknowledgement),
+    };
+
+    (cancel, Cancellation::Receiver(receiver, acknowledge))
+}
+
+/// Create a connected `Cancel` handle and `Cancellation` source for a preemptible `Streamline`
+/// whose cancellations carry a reason, along with the slot that the reason is moved into once
+/// the cancellation has been observed
+pub(crate) fn channel_with_reason<R>() -> (Cancel<R>, Cancellation, ReasonSlot<R>)
+where
+    R: 'static,
+{
+    let (sender, receiver) = oneshot::channel();
+    let (acknowledge, acknowledgement) = oneshot::channel();
+    let slot = ReasonSlot::default();
+
+    let cancel = Cancel {
+        sender,
+        acknowledgement: Some(acknowledgement),
+    };
+
+    let cancellation = Cancellation::Reason {
+        request: Box::new(ReasonRequest {
+            receiver,
+            slot: slot.clone(),
+        }),
+        acknowledge,
+        received: false,
+    };
+
+    (cancel, cancellation, slot)
+}
+
+/// Create a connected `DebouncedCancel` handle and `Cancellation` source that waits for `delay`
+/// before committing to a cancellation
+pub(crate) fn debounced(delay: Duration) -> (DebouncedCancel, Cancellation) {
+    let cancel = DebouncedCancel::default();
+
+    (cancel.clone(), Cancellation::Debounced(cancel, delay))
+}
+
+/// The slot that the reason of a cancellation is moved into once a `Streamline` has observed it,
+/// to be attached to the `RevertProgress::Cancelled` item reporting the cancellation
+pub(crate) type ReasonSlot<R> = Rc<RefCell<Option<R>>>;
+
+/// The receiving end of a `Cancel` handle that carries a reason, with the reason's type erased
+pub(crate) trait CancelRequest {
+    /// Check (without waiting) for a cancellation request, moving its reason into its slot
+    fn try_take(&mut self) -> bool;
+
+    /// Wait for a cancellation request, moving its reason into its slot
+    fn poll_take(&mut self, cx: &mut Context<'_>) -> Poll<()>;
+}
+
+/// A `CancelRequest` backed by the receiving end of a `Cancel<R>` handle
+struct ReasonRequest<R> {
+    receiver: Receiver<R>,
+    slot: ReasonSlot<R>,
+}
+
+impl<R> CancelRequest for ReasonRequest<R> {
+    fn try_take(&mut self) -> bool {
+        match self.receiver.try_recv() {
+            Ok(Some(reason)) => {
+                self.slot.replace(Some(reason));
+
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn poll_take(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        match Pin::new(&mut self.receiver).poll(cx) {
+            Poll::Ready(Ok(reason)) => {
+                self.slot.replace(Some(reason));
+
+                Poll::Ready(())
+            }
+            // cancellation can never be requested once the handle has been dropped
+            Poll::Ready(Err(_)) | Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// The source of cancellation signals polled by a `Streamline` before each forward transition
+pub(crate) enum Cancellation {
+    /// the receiving end of a `Cancel` handle created by `run_preemptible`, along with the sender
+    /// used to acknowledge that cancellation has been observed
+    Receiver(Receiver<()>, Sender<()>),
+    /// the receiving end of a `Cancel` handle created by `run_preemptible_with_reason`, along with
+    /// the sender used to acknowledge that cancellation has been observed, and whether the
+    /// reason has been received
+    Reason {
+        request: Box<dyn CancelRequest>,
+        acknowledge: Sender<()>,
+        received: bool,
+    },
+    /// an arbitrary user-provided signal passed to `run_with_cancel`
+    Signal(Pin<Box<dyn Future<Output = ()>>>),
+    /// a clearable flag set through a `DebouncedCancel` handle, along with its debounce delay
+    Debounced(DebouncedCancel, Duration),
+    /// another source combined with an extra signal (e.g. from a `Supervisor`), either of which
+    /// cancels the `Streamline`
+    Combined(Box<Cancellation>, Pin<Box<dyn Future<Output = ()>>>),
+}
+
+impl Cancellation {
+    /// Combine this source with an extra signal, so that either of them cancels the `Streamline`
+    pub(crate) fn or_signal(self, signal: Pin<Box<dyn Future<Output = ()>>>) -> Self {
+        Self::Combined(Box::new(self), signal)
+    }
+
+    /// Check whether cancellation has been requested. This only waits for debounced
+    /// cancellations, which are re-checked once their delay has passed.
+    pub(crate) async fn is_cancelled(&mut self) -> bool {
+        match self {
+            Self::Receiver(receiver, _) => matches!(receiver.try_recv(), Ok(Some(_))),
+            Self::Reason {
+                request, received, ..
+            } => {
+                *received = request.try_take();
+
+                *received
+            }
+            Self::Signal(signal) => signal.now_or_never().is_some(),
+            Self::Debounced(cancel, delay) => {
+                if !cancel.is_cancelled() {
+                    return false;
+                }
+
+                Delay::new(*delay).await;
+
+                cancel.is_cancelled()
+            }
+            Self::Combined(cancellation, signal) => {
+                Box::pin(cancellation.is_cancelled()).await || signal.now_or_never().is_some()
+            }
+        }
+    }
+
+    /// Wait until cancellation is requested. Debounced cancellations are never waited on, and
+    /// are only observed through `is_cancelled`.
+    pub(crate) async fn requested(&mut self) {
+        match self {
+            Self::Receiver(receiver, _) => {
+                if receiver.await.is_err() {
+                    future::pending::<()>().await;
+                }
+            }
+            Self::Reason {
+                request, received, ..
+            } => {
+                future::poll_fn(|cx| request.poll_take(cx)).await;
+
+                *received = true;
+            }
+            Self::Signal(signal) => {
+                signal.as_mut().await;
+
+                // keep the signal pollable for later checks
+                *signal = Box::pin(future::ready(()));
+            }
+            Self::Debounced(..) => future::pending().await,
+            Self::Combined(cancellation, signal) => {
+                let requested = Box::pin(cancellation.requested());
+
+                if let Either::Right(_) = future::select(requested, signal.as_mut()).await {
+                    // keep the signal pollable for later checks
+                    *signal = Box::pin(future::ready(()));
+                }
+            }
+        }
+    }
+
+    /// Notify any waiting `Cancel` handle that reversion has started, returning whether the
+    /// cancellation carried a reason (which has been moved into its slot)
+    pub(crate) fn acknowledge(self) -> bool {
+        match self {
+            Self::Receiver(_, acknowledge) => {
+                acknowledge.send(()).ok();
+
+                false
+            }
+            Self::Reason {
+                acknowledge,
+                received,
+                ..
+            } => {
+                acknowledge.send(()).ok();
+
+                received
+            }
+            Self::Combined(cancellation, _) => cancellation.acknowledge(),
+            _ => false,
+        }
+    }
 }
@@ -0,0 +1,57 @@
+use futures::future::LocalBoxFuture;
+
+/// An async compensation closure run in place of `State::revert`
+pub type Compensation<S, C, E> =
+    Box<dyn for<'a> Fn(&'a S, Option<&'a mut C>) -> LocalBoxFuture<'a, Result<Option<S>, E>>>;
+
+/// A table of compensations for individual states, kept separately from the `State`
+/// implementation so that forward logic and rollback logic can live in different places.
+/// States are matched against registered entries with `PartialEq`.
+///
+/// During reversion, an explicit `State::revert` implementation always takes precedence: the
+/// registry is only consulted when `revert` returns `Ok(None)` (which is what the default
+/// implementation does). Like `revert`, each compensation returns the previous state to continue
+/// reverting from, or `Ok(None)` to finish reverting.
+pub struct CompensationRegistry<S, C, E> {
+    compensations: Vec<(S, Compensation<S, C, E>)>,
+}
+
+impl<S, C, E> Default for CompensationRegistry<S, C, E> {
+    fn default() -> Self {
+        Self {
+            compensations: vec![],
+        }
+    }
+}
+
+impl<S, C, E> CompensationRegistry<S, C, E>
+where
+    S: PartialEq,
+{
+    /// Create an empty `CompensationRegistry`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a compensation for states equal to `state`, replacing any existing compensation
+    /// for that state
+    pub fn register<F>(mut self, state: S, compensation: F) -> Self
+    where
+        F: for<'a> Fn(&'a S, Option<&'a mut C>) -> LocalBoxFuture<'a, Result<Option<S>, E>>
+            + 'static,
+    {
+        self.compensations
+            .retain(|(registered, _)| registered != &state);
+        self.compensations.push((state, Box::new(compensation)));
+
+        self
+    }
+
+    /// Find the compensation registered for a state, if one exists
+    pub(crate) fn get(&self, state: &S) -> Option<&Compensation<S, C, E>> {
+        self.compensations
+            .iter()
+            .find(|(registered, _)| registered == state)
+            .map(|(_, compensation)| compensation)
+    }
+}
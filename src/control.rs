@@ -0,0 +1,40 @@
+use std::sync::{Arc, Mutex};
+
+/// Handle returned by `Streamline::run_controlled` for manually intervening in the controlled
+/// `Streamline` while it runs. Cloning a `ControlHandle` controls the same `Streamline`.
+pub struct ControlHandle<S> {
+    forced: Arc<Mutex<Option<S>>>,
+}
+
+impl<S> Clone for ControlHandle<S> {
+    fn clone(&self) -> Self {
+        Self {
+            forced: self.forced.clone(),
+        }
+    }
+}
+
+impl<S> ControlHandle<S> {
+    /// Create a handle without any pending intervention
+    pub(crate) fn new() -> Self {
+        Self {
+            forced: Arc::default(),
+        }
+    }
+
+    /// Make `state` the result of the next forward transition to start (or to retry), instead
+    /// of calling `next` on the current state. This bypasses the current state's own transition
+    /// logic entirely, along with any side effects of its `next`: use it to drive a machine
+    /// through a specific path in tests, or to nudge a stuck machine past a state by hand. An
+    /// in-flight `next` is not interrupted, and forcing another state before the forced one has
+    /// been used replaces it. Forced states are ignored while reverting, and discarded once the
+    /// `Streamline` has finished.
+    pub fn force_next(&self, state: S) {
+        *self.forced.lock().unwrap() = Some(state);
+    }
+
+    /// Take the forced state, if there is one
+    pub(crate) fn take_forced(&self) -> Option<S> {
+        self.forced.lock().unwrap().take()
+    }
+}
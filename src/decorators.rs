@@ -0,0 +1,181 @@
+//! `State` decorators that wrap an existing `State` implementation to add behavior without
+//! modifying it
+use crate::{
+    state::{ErrorCategory, State},
+    step_policy::StepPolicy,
+};
+use async_trait::async_trait;
+use std::{fmt::Debug, time::Instant};
+
+/// A `State` decorator that logs every `next` and `revert` call of the inner state through the
+/// `log` crate, without changing its behavior. Build a `Streamline` from `Logged::new(state)` to
+/// log an existing machine.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Logged<S> {
+    inner: S,
+}
+
+impl<S> Logged<S> {
+    /// Wrap a state in a logging decorator
+    pub fn new(inner: S) -> Self {
+        Self { inner }
+    }
+
+    /// Get a reference to the wrapped state
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Unwrap the decorated state
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+#[async_trait(?Send)]
+impl<S> State for Logged<S>
+where
+    S: State + Debug,
+    S::Error: Debug,
+{
+    type Context = S::Context;
+    type Error = S::Error;
+
+    async fn next(&self, context: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+        self.next_from(None, context).await
+    }
+
+    async fn next_from(
+        &self,
+        previous: Option<&Self>,
+        context: Option<&mut Self::Context>,
+    ) -> Result<Option<Self>, Self::Error> {
+        self.next_with_history(previous.cloned().as_slice(), context)
+            .await
+    }
+
+    async fn next_with_history(
+        &self,
+        history: &[Self],
+        context: Option<&mut Self::Context>,
+    ) -> Result<Option<Self>, Self::Error> {
+        log::debug!("{:?}: running next", &self.inner);
+
+        let history: Vec<_> = history.iter().map(|state| state.inner.clone()).collect();
+
+        match self.inner.next_with_history(&history, context).await {
+            Ok(Some(next)) => {
+                log::info!("{:?} -> {:?}", &self.inner, &next);
+
+                Ok(Some(Self::new(next)))
+            }
+            Ok(None) => {
+                log::info!("{:?}: finished", &self.inner);
+
+                Ok(None)
+            }
+            Err(error) => {
+                log::warn!("{:?}: next failed with {:?}", &self.inner, &error);
+
+                Err(error)
+            }
+        }
+    }
+
+    #[cfg(feature = "revert")]
+    async fn revert(
+        &self,
+        context: Option<&mut Self::Context>,
+    ) -> Result<Option<Self>, Self::Error> {
+        log::debug!("{:?}: running revert", &self.inner);
+
+        match self.inner.revert(context).await {
+            Ok(Some(previous)) => {
+                log::info!("{:?} reverted to {:?}", &self.inner, &previous);
+
+                Ok(Some(Self::new(previous)))
+            }
+            Ok(None) => {
+                log::info!("{:?}: finished reverting", &self.inner);
+
+                Ok(None)
+            }
+            Err(error) => {
+                log::error!("{:?}: revert failed with {:?}", &self.inner, &error);
+
+                Err(error)
+            }
+        }
+    }
+
+    #[cfg(feature = "revert")]
+    fn independent_revert(&self) -> bool {
+        self.inner.independent_revert()
+    }
+
+    #[cfg(feature = "revert")]
+    fn summarize_error(&self, error: Self::Error) -> Self::Error {
+        self.inner.summarize_error(error)
+    }
+
+    fn sub_steps(&self) -> Vec<String> {
+        self.inner.sub_steps()
+    }
+
+    fn take_warnings(&self) -> Vec<String> {
+        self.inner.take_warnings()
+    }
+
+    fn idempotency_key(&self) -> Option<String> {
+        self.inner.idempotency_key()
+    }
+
+    fn transitions(&self) -> Vec<Self> {
+        self.inner
+            .transitions()
+            .into_iter()
+            .map(Self::new)
+            .collect()
+    }
+
+    #[cfg(feature = "revert")]
+    fn revert_transitions(&self) -> Vec<Self> {
+        self.inner
+            .revert_transitions()
+            .into_iter()
+            .map(Self::new)
+            .collect()
+    }
+
+    fn cost(&self) -> u64 {
+        self.inner.cost()
+    }
+
+    fn delay_until(&self) -> Option<Instant> {
+        self.inner.delay_until()
+    }
+
+    fn waiting_on(&self) -> Option<String> {
+        self.inner.waiting_on()
+    }
+
+    fn is_cancellable(&self) -> bool {
+        self.inner.is_cancellable()
+    }
+
+    fn has_side_effects(&self) -> bool {
+        self.inner.has_side_effects()
+    }
+
+    fn is_final(&self) -> bool {
+        self.inner.is_final()
+    }
+
+    fn categorize(&self, error: &Self::Error) -> ErrorCategory {
+        self.inner.categorize(error)
+    }
+
+    fn step_policy(&self) -> Option<StepPolicy> {
+        self.inner.step_policy()
+    }
+}
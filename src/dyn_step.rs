@@ -0,0 +1,107 @@
+use crate::state::State;
+use async_trait::async_trait;
+use std::{fmt, rc::Rc};
+
+/// An object-safe step of a dynamically-assembled machine (e.g. one registered by a plugin at
+/// runtime), run in sequence through `DynSteps`
+#[async_trait(?Send)]
+pub trait DynStep<C, E> {
+    /// Run the step. If `Err(E)` is returned, every previously-completed step is undone in reverse
+    /// order.
+    async fn run(&self, context: Option<&mut C>) -> Result<(), E>;
+
+    /// Undo the effects of a completed step during reversion. By default, steps have nothing to
+    /// undo.
+    async fn undo(&self, _context: Option<&mut C>) -> Result<(), E> {
+        Ok(())
+    }
+}
+
+/// A `State` that runs a list of `DynStep`s in order, for machines whose steps are not known at
+/// compile time. Each state is the position in the list, i.e. the number of steps that have
+/// completed so far, so a `Streamline` over `n` steps emits `n + 1` states. Reversion walks the
+/// list backward, calling `undo` on every completed step.
+pub struct DynSteps<C, E> {
+    steps: Rc<Vec<Box<dyn DynStep<C, E>>>>,
+    position: usize,
+}
+
+impl<C, E> DynSteps<C, E> {
+    /// Create the initial state of a machine running `steps` in order
+    pub fn new(steps: Vec<Box<dyn DynStep<C, E>>>) -> Self {
+        Self {
+            steps: Rc::new(steps),
+            position: 0,
+        }
+    }
+
+    /// The number of steps that have completed
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Move to another position in the same list of steps
+    fn at(&self, position: usize) -> Self {
+        Self {
+            steps: self.steps.clone(),
+            position,
+        }
+    }
+}
+
+impl<C, E> Clone for DynSteps<C, E> {
+    fn clone(&self) -> Self {
+        self.at(self.position)
+    }
+}
+
+impl<C, E> PartialEq for DynSteps<C, E> {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.steps, &other.steps) && self.position == other.position
+    }
+}
+
+impl<C, E> fmt::Debug for DynSteps<C, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DynSteps")
+            .field("position", &self.position)
+            .field("steps", &self.steps.len())
+            .finish()
+    }
+}
+
+#[async_trait(?Send)]
+impl<C, E> State for DynSteps<C, E> {
+    type Context = C;
+    type Error = E;
+
+    async fn next(&self, context: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+        match self.steps.get(self.position) {
+            Some(step) => {
+                step.run(context).await?;
+
+                Ok(Some(self.at(self.position + 1)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    #[cfg(feature = "revert")]
+    async fn revert(
+        &self,
+        context: Option<&mut Self::Context>,
+    ) -> Result<Option<Self>, Self::Error> {
+        match self.position.checked_sub(1) {
+            Some(previous) => {
+                self.steps[previous].undo(context).await?;
+
+                Ok(Some(self.at(previous)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn is_final(&self) -> bool {
+        self.position == self.steps.len()
+    }
+}
@@ -0,0 +1,159 @@
+#[cfg(feature = "revert")]
+use crate::progress::RevertProgress;
+use crate::{progress::Progress, state::State};
+
+/// Count the distinct states reachable from `start` (inclusive) through the declared
+/// `State::transitions`, as a static denominator for progress bars that doesn't rely on
+/// `State::cost`. For acyclic machines where every run passes through every declared state, this
+/// is the total number of states a run emits while moving forward. Branching machines only visit
+/// some of their states, so the count is an upper bound for them. Cyclic machines can visit
+/// states any number of times, so each state is only counted once, and the count says little
+/// about the length of a run. States that declare no transitions are counted as final.
+pub fn count_states<S>(start: S) -> usize
+where
+    S: State,
+{
+    reachable(start).len()
+}
+
+/// Every state reachable from `start` (inclusive) through the declared `State::transitions`, in
+/// breadth-first order
+pub(crate) fn reachable<S>(start: S) -> Vec<S>
+where
+    S: State,
+{
+    let mut visited = vec![start];
+    let mut position = 0;
+
+    while let Some(state) = visited.get(position) {
+        for next in state.transitions() {
+            if !visited.contains(&next) {
+                visited.push(next);
+            }
+        }
+
+        position += 1;
+    }
+
+    visited
+}
+
+/// The total cost of the expected forward path from `state` (inclusive), following the first of
+/// each state's declared `State::transitions`, or `None` if the expected path contains a cycle
+pub(crate) fn remaining_cost<S>(mut state: S) -> Option<u64>
+where
+    S: State,
+{
+    let mut visited = vec![];
+    let mut cost = 0;
+
+    loop {
+        if visited.contains(&state) {
+            return None;
+        }
+
+        cost += state.cost();
+
+        let next = state.transitions().into_iter().next();
+
+        visited.push(state);
+
+        match next {
+            Some(next) => state = next,
+            None => return Some(cost),
+        }
+    }
+}
+
+/// The number of states on the expected reversion path from `state` (inclusive), following the
+/// first of each state's declared `State::revert_transitions`, or `None` if `state` declares no
+/// revert transitions or the expected path contains a cycle
+#[cfg(feature = "revert")]
+fn revert_path_length<S>(mut state: S) -> Option<usize>
+where
+    S: State,
+{
+    let mut visited = vec![];
+
+    if state.revert_transitions().is_empty() {
+        return None;
+    }
+
+    loop {
+        if visited.contains(&state) {
+            return None;
+        }
+
+        let previous = state.revert_transitions().into_iter().next();
+
+        visited.push(state);
+
+        match previous {
+            Some(previous) => state = previous,
+            None => return Some(visited.len()),
+        }
+    }
+}
+
+/// Tracks the fraction of a `Streamline` that has been completed (see
+/// `Streamline::run_with_progress`)
+#[derive(Default)]
+pub(crate) struct Fraction {
+    completed_cost: u64,
+    #[cfg(feature = "revert")]
+    reverted: usize,
+    #[cfg(feature = "revert")]
+    revert_path_length: Option<Option<usize>>,
+    last: Option<f64>,
+}
+
+impl Fraction {
+    /// Compute the fraction completed as of an emitted state, given whether it was the last state
+    pub(crate) fn update<S, E, C>(
+        &mut self,
+        progress: &Progress<S, E, C>,
+        last: bool,
+    ) -> Option<f64>
+    where
+        S: State<Context = C, Error = E>,
+    {
+        self.last = match progress {
+            Progress::Ok(_) if last => Some(1.0),
+            Progress::Ok(state) => {
+                let fraction = remaining_cost(state.clone()).map(|remaining| {
+                    match self.completed_cost + remaining {
+                        // only free states are left, so nothing counts as done until the end
+                        0 => 0.0,
+                        total => self.completed_cost as f64 / total as f64,
+                    }
+                });
+
+                self.completed_cost += state.cost();
+
+                fraction
+            }
+            #[cfg(feature = "revert")]
+            Progress::Revert(RevertProgress::Reverting { step, .. }) => {
+                let total = *self
+                    .revert_path_length
+                    .get_or_insert_with(|| revert_path_length(step.clone()));
+
+                let fraction = total.map(|total| (self.reverted as f64 / total as f64).min(1.0));
+
+                self.reverted += 1;
+
+                fraction
+            }
+            #[cfg(feature = "revert")]
+            Progress::Revert(RevertProgress::Reverted { .. }) => Some(1.0),
+            #[cfg(feature = "revert")]
+            Progress::Revert(RevertProgress::Failure { .. }) => None,
+            #[cfg(not(feature = "revert"))]
+            Progress::Failed { .. } => None,
+            Progress::Done => Some(1.0),
+            _ => self.last,
+        };
+
+        self.last
+    }
+}
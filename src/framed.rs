@@ -0,0 +1,86 @@
+use crate::{progress::Progress, state::State, streamline::Streamline};
+use futures::{future, stream::FusedStream, StreamExt};
+use serde::{de::DeserializeOwned, ser::Error as _, Serialize};
+use std::convert::TryInto;
+
+/// The size of the length prefix of every frame, in bytes
+const PREFIX_LENGTH: usize = 4;
+
+impl<C, E, S> Streamline<C, E, S>
+where
+    S: State<Context = C, Error = E> + Serialize,
+    E: Serialize,
+{
+    /// Generate a Stream of length-prefixed binary frames, one for each state, consuming the
+    /// `Streamline`. Frames can be sent as-is over any binary transport (e.g. as WebSocket binary
+    /// messages, or back-to-back over a TCP stream), and are read back with `decode_frames`.
+    ///
+    /// Every frame is made up of a 4-byte big-endian unsigned length, followed by that many bytes
+    /// of the `Progress` serialized as JSON through its `Serialize` implementation.
+    /// `Progress::Started` markers can not be serialized, so they are skipped. Items that fail to
+    /// serialize (or whose payload doesn't fit in a 4 GiB frame) are reported as errors in place
+    /// of their frame, and the Stream carries on with the next item.
+    pub fn run_framed(self) -> impl FusedStream<Item = Result<Vec<u8>, serde_json::Error>> {
+        self.run().filter_map(|progress| {
+            let frame = match progress {
+                Progress::Started(_) => None,
+                progress => Some(encode_frame(&progress)),
+            };
+
+            future::ready(frame)
+        })
+    }
+}
+
+fn encode_frame<S, E, C>(progress: &Progress<S, E, C>) -> Result<Vec<u8>, serde_json::Error>
+where
+    S: State<Context = C, Error = E> + Serialize,
+    E: Serialize,
+{
+    let payload = serde_json::to_vec(progress)?;
+    let length: u32 = payload
+        .len()
+        .try_into()
+        .map_err(|_| serde_json::Error::custom("payload is too large for a single frame"))?;
+
+    let mut frame = Vec::with_capacity(PREFIX_LENGTH + payload.len());
+
+    frame.extend_from_slice(&length.to_be_bytes());
+    frame.extend(payload);
+
+    Ok(frame)
+}
+
+/// Decode every complete frame (as produced by `Streamline::run_framed`) from the front of
+/// `buffer`, removing them from the buffer. Any trailing partial frame is left in the buffer, so
+/// bytes can be appended as they arrive and decoded incrementally. Every frame is decoded on its
+/// own: a frame that fails to deserialize is reported as an error in its place, and is removed
+/// from the buffer like any other, so that decoding carries on with the frames that follow it.
+pub fn decode_frames<S, E, C>(
+    buffer: &mut Vec<u8>,
+) -> Vec<Result<Progress<S, E, C>, serde_json::Error>>
+where
+    S: State<Context = C, Error = E> + DeserializeOwned,
+    E: DeserializeOwned,
+{
+    let mut progress = vec![];
+    let mut offset = 0;
+
+    while let Some(prefix) = buffer.get(offset..offset + PREFIX_LENGTH) {
+        let length =
+            u32::from_be_bytes(prefix.try_into().expect("prefix has a fixed length")) as usize;
+        let start = offset + PREFIX_LENGTH;
+
+        let payload = match buffer.get(start..start + length) {
+            Some(payload) => payload,
+            None => break,
+        };
+
+        progress.push(serde_json::from_slice(payload));
+        offset = start + length;
+    }
+
+    buffer.drain(..offset);
+
+    progress
+}
@@ -0,0 +1,54 @@
+use crate::state::State;
+use std::{collections::VecDeque, error::Error, fmt};
+
+/// A forward transition without a corresponding revert transition, as found by `validate_graph`
+#[derive(Clone, Debug, PartialEq)]
+pub struct GraphError<S> {
+    /// the state that transitions forward to `to`
+    pub from: S,
+    /// the state that does not declare a revert transition back to `from`
+    pub to: S,
+}
+
+impl<S> fmt::Display for GraphError<S>
+where
+    S: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} transitions to {:?}, which can not revert back to it",
+            self.from, self.to
+        )
+    }
+}
+
+impl<S> Error for GraphError<S> where S: fmt::Debug {}
+
+/// Statically check that every forward transition reachable from `start` can be reverted, i.e.
+/// that for every `A -> B` declared through `State::transitions`, `B` declares a revert
+/// transition back to `A` through `State::revert_transitions`. The graph is walked breadth-first,
+/// and the first missing revert transition is returned. This only covers the declared edges:
+/// transitions taken by `next` and `revert` at runtime are never checked.
+pub fn validate_graph<S>(start: S) -> Result<(), GraphError<S>>
+where
+    S: State,
+{
+    let mut visited = vec![start.clone()];
+    let mut queue = VecDeque::from(vec![start]);
+
+    while let Some(from) = queue.pop_front() {
+        for to in from.transitions() {
+            if !to.revert_transitions().contains(&from) {
+                return Err(GraphError { from, to });
+            }
+
+            if !visited.contains(&to) {
+                visited.push(to.clone());
+                queue.push_back(to);
+            }
+        }
+    }
+
+    Ok(())
+}
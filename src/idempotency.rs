@@ -0,0 +1,104 @@
+use async_trait::async_trait;
+use std::{collections::HashMap, sync::Arc, sync::Mutex};
+
+/// Pluggable record of which states have already run, consulted by an idempotent `Streamline`
+/// (see `Streamline::idempotent`) to skip states whose side effects have already happened.
+#[async_trait(?Send)]
+pub trait IdempotencyStore<S> {
+    /// Look up a previously-run state by its `State::idempotency_key`. Returns `None` if the state
+    /// has not run yet, or `Some(next)` with the transition that was recorded when it did.
+    async fn has_run(&self, key: &str) -> Option<Option<S>>;
+
+    /// Record that the state identified by `key` has run successfully, transitioning to `next`
+    async fn mark_run(&self, key: String, next: Option<S>);
+}
+
+#[async_trait(?Send)]
+impl<S, T> IdempotencyStore<S> for Arc<T>
+where
+    T: IdempotencyStore<S> + ?Sized,
+    S: 'static,
+{
+    async fn has_run(&self, key: &str) -> Option<Option<S>> {
+        (**self).has_run(key).await
+    }
+
+    async fn mark_run(&self, key: String, next: Option<S>) {
+        (**self).mark_run(key, next).await
+    }
+}
+
+/// A record of which states have already run kept within the context itself, consulted by a
+/// `Streamline` built with `Streamline::idempotent_in_context`. Unlike an `IdempotencyStore`, the
+/// record lives alongside the rest of the context, so it is persisted (e.g. through a
+/// checkpoint) and restored together with it, and a resumed `Streamline` skips every state that
+/// already ran before it was stopped.
+pub trait ContextIdempotency<S> {
+    /// Look up a previously-run state by its `State::idempotency_key`. Returns `None` if the state
+    /// has not run yet, or `Some(next)` with the transition that was recorded when it did.
+    fn has_run(&self, key: &str) -> Option<Option<S>>;
+
+    /// Record that the state identified by `key` has run successfully, transitioning to `next`
+    fn mark_run(&mut self, key: String, next: Option<S>);
+}
+
+/// The `ContextIdempotency` implementation of a context, captured as function pointers
+pub(crate) struct ContextRecord<C, S> {
+    pub(crate) has_run: fn(&C, &str) -> Option<Option<S>>,
+    pub(crate) mark_run: fn(&mut C, String, Option<S>),
+}
+
+impl<C, S> ContextRecord<C, S>
+where
+    C: ContextIdempotency<S>,
+{
+    pub(crate) fn new() -> Self {
+        Self {
+            has_run: C::has_run,
+            mark_run: C::mark_run,
+        }
+    }
+}
+
+/// A simple in-memory `IdempotencyStore`, mostly useful for testing. Wrap it in an `Arc` to share
+/// the same record across several runs of a `Streamline`.
+#[derive(Debug)]
+pub struct MemoryStore<S> {
+    completed: Mutex<HashMap<String, Option<S>>>,
+}
+
+impl<S> Default for MemoryStore<S> {
+    fn default() -> Self {
+        Self {
+            completed: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<S> MemoryStore<S> {
+    /// Create an empty `MemoryStore`
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait(?Send)]
+impl<S> IdempotencyStore<S> for MemoryStore<S>
+where
+    S: Clone + 'static,
+{
+    async fn has_run(&self, key: &str) -> Option<Option<S>> {
+        self.completed
+            .lock()
+            .expect("could not get lock on idempotency store")
+            .get(key)
+            .cloned()
+    }
+
+    async fn mark_run(&self, key: String, next: Option<S>) {
+        self.completed
+            .lock()
+            .expect("could not get lock on idempotency store")
+            .insert(key, next);
+    }
+}
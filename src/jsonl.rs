@@ -0,0 +1,126 @@
+#[cfg(feature = "revert")]
+use crate::progress::RevertProgress;
+use crate::{progress::Progress, state::State, streamline::Streamline};
+use futures::{stream::FusedStream, StreamExt};
+use serde_json::json;
+use std::fmt::Display;
+#[cfg(feature = "revert")]
+use std::sync::Arc;
+
+impl<C, E, S> Streamline<C, E, S>
+where
+    S: State<Context = C, Error = E> + Display,
+    E: Display,
+{
+    /// Generate a Stream of single-line JSON objects describing each state, consuming the
+    /// `Streamline`. States and errors are described through their `Display` implementations
+    /// (like `run_sse`), so neither needs to implement `Serialize`. Every line has a `phase`
+    /// field, with the remaining fields depending on the phase:
+    ///
+    /// - `{"phase": "ok", "state": string}`
+    /// - `{"phase": "sub_step", "state": string, "index": number, "label": string}`
+    /// - `{"phase": "warning", "state": string, "message": string}`
+    /// - `{"phase": "cancelled", "state": string}`
+    /// - `{"phase": "reverting", "state": string, "source": string | null}`
+    /// - `{"phase": "reverted", "source": string | null}`
+    /// - `{"phase": "failure", "source": string | null, "error": string}`
+    /// - `{"phase": "failed", "source": string | null}` (without the `revert` feature)
+    /// - `{"phase": "done"}`
+    /// - `{"phase": "started"}`
+    /// - `{"phase": "cancellation_requested", "state": string}`
+    /// - `{"phase": "idle", "state": string}`
+    /// - `{"phase": "waiting", "state": string, "reason": string}`
+    /// - `{"phase": "truncated"}`
+    ///
+    /// `source` is the error that triggered reversion, and is `null` for cancellations.
+    pub fn run_jsonl(self) -> impl FusedStream<Item = String> {
+        self.run().map(|progress| to_json_line(&progress))
+    }
+}
+
+fn to_json_line<S, E, C>(progress: &Progress<S, E, C>) -> String
+where
+    S: State<Context = C, Error = E> + Display,
+    E: Display,
+{
+    #[cfg(feature = "revert")]
+    let describe_source = |source: &Option<Arc<E>>| source.as_ref().map(ToString::to_string);
+
+    let line = match progress {
+        Progress::Ok(state) => json!({
+            "phase": "ok",
+            "state": state.to_string(),
+        }),
+        Progress::SubStep {
+            state,
+            index,
+            label,
+        } => json!({
+            "phase": "sub_step",
+            "state": state.to_string(),
+            "index": index,
+            "label": label,
+        }),
+        Progress::Warning { state, message } => json!({
+            "phase": "warning",
+            "state": state.to_string(),
+            "message": message,
+        }),
+        #[cfg(feature = "revert")]
+        Progress::Revert(RevertProgress::Cancelled { step, .. }) => json!({
+            "phase": "cancelled",
+            "state": step.to_string(),
+        }),
+        #[cfg(feature = "revert")]
+        Progress::Revert(RevertProgress::Reverting { step, source }) => json!({
+            "phase": "reverting",
+            "state": step.to_string(),
+            "source": describe_source(source),
+        }),
+        #[cfg(feature = "revert")]
+        Progress::Revert(RevertProgress::Reverted { source, .. }) => json!({
+            "phase": "reverted",
+            "source": describe_source(source),
+        }),
+        #[cfg(feature = "revert")]
+        Progress::Revert(RevertProgress::Failure { source, error }) => json!({
+            "phase": "failure",
+            "source": describe_source(source),
+            "error": error.to_string(),
+        }),
+        #[cfg(not(feature = "revert"))]
+        Progress::Failed { source } => json!({
+            "phase": "failed",
+            "source": source.as_ref().map(ToString::to_string),
+        }),
+        #[cfg(not(feature = "revert"))]
+        Progress::Cancelled { step, .. } => json!({
+            "phase": "cancelled",
+            "state": step.to_string(),
+        }),
+        Progress::Done => json!({
+            "phase": "done",
+        }),
+        Progress::Started(_) => json!({
+            "phase": "started",
+        }),
+        Progress::CancellationRequested { step } => json!({
+            "phase": "cancellation_requested",
+            "state": step.to_string(),
+        }),
+        Progress::Idle { state } => json!({
+            "phase": "idle",
+            "state": state.to_string(),
+        }),
+        Progress::Waiting { state, reason } => json!({
+            "phase": "waiting",
+            "state": state.to_string(),
+            "reason": reason,
+        }),
+        Progress::Truncated => json!({
+            "phase": "truncated",
+        }),
+    };
+
+    line.to_string()
+}
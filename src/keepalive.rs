@@ -0,0 +1,48 @@
+use crate::{progress::Progress, state::State, streamline::Streamline};
+use futures::{
+    future::{self, Either},
+    stream::{self, FusedStream},
+    StreamExt,
+};
+use futures_timer::Delay;
+use std::time::Duration;
+
+/// A marker emitted by `Streamline::run_with_keepalive` whenever no progress has been made for a
+/// whole interval
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct KeepAlive;
+
+impl<C, E, S> Streamline<C, E, S>
+where
+    S: State<Context = C, Error = E>,
+{
+    /// Generate a Stream of states interleaved with `KeepAlive` markers, consuming the
+    /// `Streamline`. A marker is emitted every `interval` without any progress, and every state
+    /// resets the interval, so that transports that close idle connections (e.g. HTTP/2 or
+    /// WebSocket proxies) stay open while a `Streamline` waits on long transitions. States are
+    /// wrapped in `Either::Left`, and markers in `Either::Right`. The interval is timed through
+    /// `futures-timer`, which runs its own timer thread, so no particular async runtime is
+    /// required.
+    pub fn run_with_keepalive(
+        self,
+        interval: Duration,
+    ) -> impl FusedStream<Item = Either<Progress<S, E, C>, KeepAlive>> {
+        let progress = Box::pin(self.run());
+
+        stream::unfold(
+            (progress, Delay::new(interval)),
+            move |(mut progress, mut delay)| async move {
+                let item = match future::select(progress.next(), &mut delay).await {
+                    Either::Left((None, _)) => return None,
+                    Either::Left((Some(progress), _)) => Either::Left(progress),
+                    Either::Right(_) => Either::Right(KeepAlive),
+                };
+
+                delay.reset(interval);
+
+                Some((item, (progress, delay)))
+            },
+        )
+        .fuse()
+    }
+}
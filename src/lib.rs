@@ -2,14 +2,100 @@
 This crates provides a state machine implementation that emits states as a `std::futures::Stream`,
 groups sources of external state into a single `Context`, and handles automatic conversion between states
 (both forwards and backwards) through the `State` trait.
+
+Reversion is enabled through the default `revert` feature. Forward-only builds can disable it to
+remove `State::revert`, `RevertProgress`, and the APIs built on top of them: errors returned from
+`next` (and cancellations) then end the `Streamline` with a terminal `Progress::Failed` instead.
 */
 #![deny(missing_docs, unreachable_pub)]
+#[cfg(feature = "revert")]
+mod abort;
+#[cfg(feature = "revert")]
+mod action;
+#[cfg(feature = "tokio")]
+mod async_context;
+mod batch;
+mod breaker;
 mod cancel;
+#[cfg(feature = "revert")]
+mod compensation;
+mod control;
+#[cfg(feature = "log")]
+pub mod decorators;
+mod dyn_step;
+mod fraction;
+#[cfg(feature = "serde")]
+mod framed;
+#[cfg(feature = "revert")]
+mod graph;
+mod idempotency;
+#[cfg(feature = "serde")]
+mod jsonl;
+mod keepalive;
+mod log_capture;
+mod monotonic;
+mod outcome;
 mod progress;
+mod retry;
+mod rng;
+mod running;
+mod shadow;
+mod shared_context;
+mod sse;
 mod state;
+mod step_policy;
 mod streamline;
+mod supervisor;
+#[cfg(feature = "opentelemetry")]
+mod telemetry;
+mod termination;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "timestamps")]
+mod timestamp;
+mod trace;
+mod updates;
+mod versioned;
+mod wal;
 
-pub use self::cancel::Cancel;
+#[cfg(feature = "revert")]
+pub use self::abort::*;
+#[cfg(feature = "revert")]
+pub use self::action::Action;
+#[cfg(feature = "tokio")]
+pub use self::async_context::AsyncContext;
+#[cfg(feature = "revert")]
+pub use self::batch::BatchState;
+pub use self::batch::{next_batch, next_batch_parallel};
+pub use self::breaker::{CircuitBreaker, CircuitOpen};
+pub use self::cancel::{Cancel, CancelReceiver, DebouncedCancel};
+#[cfg(feature = "revert")]
+pub use self::compensation::*;
+pub use self::control::ControlHandle;
+pub use self::dyn_step::*;
+pub use self::fraction::count_states;
+#[cfg(feature = "serde")]
+pub use self::framed::decode_frames;
+#[cfg(feature = "revert")]
+pub use self::graph::*;
+pub use self::idempotency::*;
+pub use self::keepalive::KeepAlive;
+pub use self::log_capture::{LogCapture, LogLine};
+pub use self::monotonic::NotMonotonic;
+pub use self::outcome::*;
 pub use self::progress::*;
+pub use self::retry::{Jitter, RetryPolicy};
+pub use self::running::RunningStreamline;
+pub use self::shadow::{shadow, Divergence};
+pub use self::shared_context::SharedContext;
 pub use self::state::*;
+pub use self::step_policy::{StepPolicy, StepTimedOut};
 pub use self::streamline::*;
+pub use self::supervisor::*;
+pub use self::termination::UnexpectedTermination;
+#[cfg(feature = "timestamps")]
+pub use self::timestamp::rfc3339;
+pub use self::trace::TraceHandle;
+pub use self::updates::*;
+pub use self::versioned::{ContextModified, Versioned};
+pub use self::wal::{MemoryWal, WriteAheadLog};
@@ -0,0 +1,66 @@
+use std::{
+    mem,
+    ops::{Deref, DerefMut},
+};
+
+/// A line logged by a state through a `LogCapture` context, as emitted by
+/// `Streamline::run_with_logs`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LogLine {
+    /// the logged message
+    pub message: String,
+}
+
+/// A context wrapper that captures log lines written by states, for interleaving them with
+/// state transitions through `Streamline::run_with_logs`. The wrapped context is available
+/// through `Deref` and `DerefMut`. Lines are buffered until the `Streamline` collects them after
+/// every transition, so memory use only grows with the lines logged during a single transition.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LogCapture<C> {
+    inner: C,
+    lines: Vec<LogLine>,
+}
+
+impl<C> LogCapture<C> {
+    /// Wrap a context, starting without any captured lines
+    pub fn new(inner: C) -> Self {
+        Self {
+            inner,
+            lines: vec![],
+        }
+    }
+
+    /// Capture a log line
+    pub fn log<M>(&mut self, message: M)
+    where
+        M: Into<String>,
+    {
+        self.lines.push(LogLine {
+            message: message.into(),
+        });
+    }
+
+    /// Unwrap the context, discarding any lines that haven't been collected yet
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+
+    /// Take every line captured so far, in the order they were logged
+    pub(crate) fn take_lines(&mut self) -> Vec<LogLine> {
+        mem::take(&mut self.lines)
+    }
+}
+
+impl<C> Deref for LogCapture<C> {
+    type Target = C;
+
+    fn deref(&self) -> &C {
+        &self.inner
+    }
+}
+
+impl<C> DerefMut for LogCapture<C> {
+    fn deref_mut(&mut self) -> &mut C {
+        &mut self.inner
+    }
+}
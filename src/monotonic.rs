@@ -0,0 +1,27 @@
+use std::{error::Error, fmt};
+
+/// The error used to start reverting a `Streamline` built with `Streamline::enforce_monotonic`
+/// when a forward transition produces a state that isn't greater than the state it came from
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NotMonotonic;
+
+impl fmt::Display for NotMonotonic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("state did not move forward")
+    }
+}
+
+impl Error for NotMonotonic {}
+
+/// Check that a forward transition moved from `current` to a strictly greater state
+pub(crate) fn check<S, E>(current: &S, next: &S) -> Result<(), E>
+where
+    S: PartialOrd,
+    E: From<NotMonotonic>,
+{
+    if next > current {
+        Ok(())
+    } else {
+        Err(E::from(NotMonotonic))
+    }
+}
@@ -0,0 +1,107 @@
+#[cfg(feature = "revert")]
+use crate::progress::RevertProgress;
+use crate::{progress::Progress, state::State};
+#[cfg(feature = "revert")]
+use std::{sync::Arc, time::Duration};
+
+/// How a `Streamline` ended, as passed to the `Streamline::finally` callback
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TerminalOutcome {
+    /// The `Streamline` ran forward to completion
+    Completed,
+    /// The `Streamline` reverted successfully after an error
+    Reverted,
+    /// The `Streamline` failed to revert, was aborted, or failed (without the `revert` feature)
+    Failed,
+    /// The `Streamline` was cancelled (or ran out of time) and reverted successfully (or stopped,
+    /// without the `revert` feature), or was dropped or truncated (through
+    /// `Streamline::max_emissions`) before finishing. Cancellations that carry a reason (see
+    /// `RevertProgress::Cancelled`) end the same way as any other cancellation.
+    Cancelled,
+}
+
+impl TerminalOutcome {
+    /// Classify the last state emitted by a finished `Streamline`
+    pub(crate) fn of<S, E, C, R>(last: &Progress<S, E, C, R>) -> Self
+    where
+        S: State<Context = C, Error = E>,
+    {
+        match last {
+            #[cfg(feature = "revert")]
+            Progress::Revert(
+                RevertProgress::Cancelled { .. } | RevertProgress::Reverted { source: None, .. },
+            ) => Self::Cancelled,
+            #[cfg(not(feature = "revert"))]
+            Progress::Cancelled { .. } => Self::Cancelled,
+            #[cfg(feature = "revert")]
+            Progress::Revert(RevertProgress::Reverted { .. }) => Self::Reverted,
+            #[cfg(not(feature = "revert"))]
+            Progress::Failed { source: None } => Self::Cancelled,
+            #[cfg(feature = "revert")]
+            Progress::Revert(RevertProgress::Failure { .. }) => Self::Failed,
+            #[cfg(not(feature = "revert"))]
+            Progress::Failed { .. } => Self::Failed,
+            Progress::Truncated => Self::Cancelled,
+            _ => Self::Completed,
+        }
+    }
+}
+
+/// The way a `Streamline` ended, as returned from `Streamline::outcome`
+#[cfg(feature = "revert")]
+#[derive(Debug, PartialEq)]
+pub enum Outcome<S, E> {
+    /// The `Streamline` ran forward to completion
+    Completed(S),
+    /// The `Streamline` reverted successfully
+    Reverted {
+        /// the original error that triggered the reversion process, if one exists
+        source: Option<Arc<E>>,
+    },
+    /// The `Streamline` failed to revert
+    Failed {
+        /// the original error that triggered the reversion process, if one exists
+        source: Option<Arc<E>>,
+        /// the error that caused the reversion process to fail
+        error: E,
+    },
+}
+
+/// A summary of a completed run, as returned from `Streamline::run_report`
+#[cfg(feature = "revert")]
+#[derive(Debug, PartialEq)]
+pub struct RunReport<S, E> {
+    /// every state visited, in order: states reached while moving forward followed by the states
+    /// reverted (if any)
+    pub path: Vec<S>,
+    /// how the `Streamline` ended, or `None` if it had already finished or was truncated through
+    /// `Streamline::max_emissions`
+    pub outcome: Option<Outcome<S, E>>,
+    /// the time taken to drive the `Streamline` to completion
+    pub duration: Duration,
+    /// the number of forward transitions between the states visited while moving forward
+    pub transitions: usize,
+    /// the number of states reverted, including the state whose transition failed
+    pub reversions: usize,
+}
+
+#[cfg(feature = "revert")]
+impl<S, E> RunReport<S, E> {
+    /// Check whether the `Streamline` reverted (successfully or not)
+    pub fn reverted(&self) -> bool {
+        matches!(
+            self.outcome,
+            Some(Outcome::Reverted { .. } | Outcome::Failed { .. })
+        )
+    }
+
+    /// The error that triggered reversion, if the `Streamline` reverted because of one
+    pub fn error(&self) -> Option<&E> {
+        match &self.outcome {
+            Some(Outcome::Reverted { source } | Outcome::Failed { source, .. }) => {
+                source.as_deref()
+            }
+            _ => None,
+        }
+    }
+}
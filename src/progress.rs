@@ -1,12 +1,36 @@
 use crate::state::State;
+#[cfg(feature = "revert")]
 use std::sync::Arc;
+use std::time::Instant;
 
 /// An internal state machine that represents the process of reverting previous progress.
+///
+/// `R` is the type of the reason carried by cancellations (see `RevertProgress::Cancelled`),
+/// which is `()` for every `Streamline` except those run through
+/// `Streamline::run_preemptible_with_reason`.
+#[cfg(feature = "revert")]
 #[derive(Debug, PartialEq)]
-pub enum RevertProgress<S, E, C>
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound(
+        serialize = "S: serde::Serialize, E: serde::Serialize, R: serde::Serialize",
+        deserialize = "S: serde::Deserialize<'de>, E: serde::Deserialize<'de>, R: serde::Deserialize<'de>"
+    ))
+)]
+pub enum RevertProgress<S, E, C, R = ()>
 where
     S: State<Context = C, Error = E>,
 {
+    /// Emitted right before the first `RevertProgress::Reverting` item of a `Streamline` that
+    /// was cancelled through a `Cancel` handle carrying a reason (see
+    /// `Streamline::run_preemptible_with_reason`). The reversion that follows has no `source`.
+    Cancelled {
+        /// the reason passed to `Cancel::cancel_with_reason`
+        reason: R,
+        /// the state whose forward transition was cancelled
+        step: S,
+    },
     /// An in-flight `State` reversion
     Reverting {
         /// the state variant in the process of being reverted
@@ -18,6 +42,12 @@ where
     Reverted {
         /// the original error that triggered the reversion process
         source: Option<Arc<E>>,
+        /// the reverted states that had a compensating action, in the order they were reverted:
+        /// states whose `revert` moved on to a previous state, or that ran a compensation
+        /// from a `CompensationRegistry`. Reverted states missing from this list
+        /// were no-ops.
+        #[cfg_attr(feature = "serde", serde(default))]
+        compensated_states: Vec<S>,
     },
     /// The final state of a failed reversion
     Failure {
@@ -28,20 +58,273 @@ where
     },
 }
 
+// implemented by hand to avoid requiring a `Clone`-able context
+#[cfg(feature = "revert")]
+impl<S, E, C, R> Clone for RevertProgress<S, E, C, R>
+where
+    S: State<Context = C, Error = E>,
+    E: Clone,
+    R: Clone,
+{
+    fn clone(&self) -> Self {
+        match self {
+            Self::Cancelled { reason, step } => Self::Cancelled {
+                reason: reason.clone(),
+                step: step.clone(),
+            },
+            Self::Reverting { step, source } => Self::Reverting {
+                step: step.clone(),
+                source: source.clone(),
+            },
+            Self::Reverted {
+                source,
+                compensated_states,
+            } => Self::Reverted {
+                source: source.clone(),
+                compensated_states: compensated_states.clone(),
+            },
+            Self::Failure { source, error } => Self::Failure {
+                source: source.clone(),
+                error: error.clone(),
+            },
+        }
+    }
+}
+
+#[cfg(feature = "revert")]
+impl<S, E, C, R> RevertProgress<S, E, C, R>
+where
+    S: State<Context = C, Error = E>,
+    E: Clone,
+{
+    /// Clone the original error that triggered the reversion process (if one exists) out of its
+    /// `Arc`. Requires a `Clone`-able error type; use the `source` field directly otherwise.
+    pub fn cloned_source(&self) -> Option<E> {
+        match self {
+            Self::Reverting { source, .. }
+            | Self::Reverted { source, .. }
+            | Self::Failure { source, .. } => source.as_deref().cloned(),
+            Self::Cancelled { .. } => None,
+        }
+    }
+}
+
+#[cfg(feature = "revert")]
+impl<S, E, C, R> RevertProgress<S, E, C, R>
+where
+    S: State<Context = C, Error = E>,
+{
+    /// A stable name for the current phase of the reversion process (`"cancelled"`,
+    /// `"reverting"`, `"reverted"`, or `"failure"`), for use in metrics tags and log fields
+    pub fn phase_name(&self) -> &'static str {
+        match self {
+            Self::Cancelled { .. } => "cancelled",
+            Self::Reverting { .. } => "reverting",
+            Self::Reverted { .. } => "reverted",
+            Self::Failure { .. } => "failure",
+        }
+    }
+}
+
+/// The broad phase of a `Streamline` that a `Progress` item belongs to, as returned from
+/// `Progress::phase`
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Phase {
+    /// Moving forward through states, including sub-steps, warnings, and idle, waiting, and
+    /// cancellation markers
+    Forward,
+    /// Reverting previous states
+    Reverting,
+    /// Finished reverting successfully
+    Reverted,
+    /// Failed to revert (or failed, without the `revert` feature)
+    Failed,
+}
+
 /// The state emitted by a `Streamline`
+///
+/// `R` is the type of the reason carried by cancellations (see `RevertProgress::Cancelled`, or
+/// `Progress::Cancelled` without the `revert` feature). It defaults to `()`, which is what every
+/// `Streamline` emits, except for the Stream returned from
+/// `Streamline::run_preemptible_with_reason`, whose items carry the reason type of its `Cancel`
+/// handle. Code that handles any kind of `Progress` can stay generic over `R`.
 #[derive(Debug, PartialEq)]
-pub enum Progress<S, E, C>
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound(
+        serialize = "S: serde::Serialize, E: serde::Serialize, R: serde::Serialize",
+        deserialize = "S: serde::Deserialize<'de>, E: serde::Deserialize<'de>, R: serde::Deserialize<'de>"
+    ))
+)]
+pub enum Progress<S, E, C, R = ()>
 where
     S: State<Context = C, Error = E>,
 {
     /// All user-provided states run as part of `Progress::Ok` until they trigger a reversion
     Ok(S),
+    /// An intermediate step of a state's transition, as declared by `State::sub_steps`. Sub-steps
+    /// are emitted after the `Progress::Ok` of the state that declared them, once its transition
+    /// has completed successfully.
+    SubStep {
+        /// the state whose transition the sub-step is part of
+        state: S,
+        /// the position of the sub-step within the transition
+        index: usize,
+        /// a description of the sub-step
+        label: String,
+    },
+    /// A non-fatal warning raised while transitioning into `state` (through
+    /// `State::take_warnings`). Warnings are emitted just before the `Progress::Ok` of the state
+    /// that raised them, and never trigger a reversion.
+    Warning {
+        /// the state that raised the warning
+        state: S,
+        /// a description of the warning
+        message: String,
+    },
     /// Once a reversion has been triggered, `Progress` tracks the state of the reversion through
     /// a `RevertProgress` `enum`
-    Revert(RevertProgress<S, E, C>),
+    #[cfg(feature = "revert")]
+    Revert(RevertProgress<S, E, C, R>),
+    /// Without the `revert` feature, errors are terminal: the final state of a `Streamline` that
+    /// stopped because of an error (or cancellation) without completing
+    #[cfg(not(feature = "revert"))]
+    Failed {
+        /// the error returned from `next`, or `None` if the `Streamline` was cancelled
+        source: Option<E>,
+    },
+    /// Without the `revert` feature, emitted right before the final `Progress::Failed` of a
+    /// `Streamline` that was cancelled through a `Cancel` handle carrying a reason (like
+    /// `RevertProgress::Cancelled`)
+    #[cfg(not(feature = "revert"))]
+    Cancelled {
+        /// the reason passed to `Cancel::cancel_with_reason`
+        reason: R,
+        /// the state whose forward transition was cancelled
+        step: S,
+    },
+    /// A marker emitted after every other state of a `Streamline` once it has finished, if
+    /// enabled through `Streamline::emit_completion_marker`
+    Done,
+    /// A marker emitted before every other state of a `Streamline`, carrying the moment at which
+    /// the `Streamline` started running, if enabled through `Streamline::emit_start_marker`.
+    /// `Instant`s can not be serialized, so this variant is skipped during serialization.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Started(Instant),
+    /// Emitted (at most once) as soon as a `Streamline` observes a cancellation request from a
+    /// `Cancel` handle or cancellation signal, right before it starts acting on it (i.e. before
+    /// the first `RevertProgress::Reverting` item). Deadlines don't emit this marker.
+    CancellationRequested {
+        /// the state whose forward transition was cancelled
+        step: S,
+    },
+    /// Emitted right before a `Streamline` sits idle, waiting to run the forward transition of
+    /// `state` until the moment given by `State::delay_until`, if enabled through
+    /// `Streamline::emit_idle_marker`. States that don't wait never emit this marker.
+    Idle {
+        /// the state whose forward transition is waiting
+        state: S,
+    },
+    /// Emitted right before the forward transition of `state` blocks on an external resource
+    /// (e.g. a lock or a rate-limit slot), as reported by `State::waiting_on`. `state` stays
+    /// current until its transition completes, and is not emitted again. Unlike
+    /// `Progress::Idle`, which means that there is no work to do until a set moment,
+    /// `Progress::Waiting` means that the transition is ready to run but blocked on something
+    /// outside of the `Streamline`, for an unknown amount of time.
+    Waiting {
+        /// the state whose forward transition is waiting
+        state: S,
+        /// a description of what the transition is waiting on
+        reason: String,
+    },
+    /// The final state of a `Streamline` that was stopped (without reverting) after emitting the
+    /// maximum number of items set through `Streamline::max_emissions`
+    Truncated,
 }
 
-impl<S, E, C> From<S> for Progress<S, E, C>
+impl<S, E, C, R> Progress<S, E, C, R>
+where
+    S: State<Context = C, Error = E>,
+{
+    /// The `Phase` that this item belongs to, or `None` for the `Progress::Started`,
+    /// `Progress::Done`, and `Progress::Truncated` markers, which come before and after every
+    /// phase
+    pub fn phase(&self) -> Option<Phase> {
+        match self {
+            Self::Ok(_)
+            | Self::SubStep { .. }
+            | Self::Warning { .. }
+            | Self::CancellationRequested { .. }
+            | Self::Idle { .. }
+            | Self::Waiting { .. } => Some(Phase::Forward),
+            #[cfg(feature = "revert")]
+            Self::Revert(RevertProgress::Cancelled { .. } | RevertProgress::Reverting { .. }) => {
+                Some(Phase::Reverting)
+            }
+            #[cfg(feature = "revert")]
+            Self::Revert(RevertProgress::Reverted { .. }) => Some(Phase::Reverted),
+            #[cfg(feature = "revert")]
+            Self::Revert(RevertProgress::Failure { .. }) => Some(Phase::Failed),
+            #[cfg(not(feature = "revert"))]
+            Self::Failed { .. } | Self::Cancelled { .. } => Some(Phase::Failed),
+            Self::Done | Self::Started(_) | Self::Truncated => None,
+        }
+    }
+}
+
+// implemented by hand to avoid requiring a `Clone`-able context
+impl<S, E, C, R> Clone for Progress<S, E, C, R>
+where
+    S: State<Context = C, Error = E>,
+    E: Clone,
+    R: Clone,
+{
+    fn clone(&self) -> Self {
+        match self {
+            Self::Ok(state) => Self::Ok(state.clone()),
+            Self::SubStep {
+                state,
+                index,
+                label,
+            } => Self::SubStep {
+                state: state.clone(),
+                index: *index,
+                label: label.clone(),
+            },
+            Self::Warning { state, message } => Self::Warning {
+                state: state.clone(),
+                message: message.clone(),
+            },
+            #[cfg(feature = "revert")]
+            Self::Revert(revert) => Self::Revert(revert.clone()),
+            #[cfg(not(feature = "revert"))]
+            Self::Failed { source } => Self::Failed {
+                source: source.clone(),
+            },
+            #[cfg(not(feature = "revert"))]
+            Self::Cancelled { reason, step } => Self::Cancelled {
+                reason: reason.clone(),
+                step: step.clone(),
+            },
+            Self::Done => Self::Done,
+            Self::Started(started) => Self::Started(*started),
+            Self::CancellationRequested { step } => {
+                Self::CancellationRequested { step: step.clone() }
+            }
+            Self::Idle { state } => Self::Idle {
+                state: state.clone(),
+            },
+            Self::Waiting { state, reason } => Self::Waiting {
+                state: state.clone(),
+                reason: reason.clone(),
+            },
+            Self::Truncated => Self::Truncated,
+        }
+    }
+}
+
+impl<S, E, C, R> From<S> for Progress<S, E, C, R>
 where
     S: State<Context = C, Error = E>,
 {
@@ -49,3 +332,65 @@ where
         Self::Ok(state)
     }
 }
+
+impl<S, E, C> Progress<S, E, C>
+where
+    S: State<Context = C, Error = E>,
+{
+    /// Attach a typed cancellation reason to an item emitted by a `Streamline`, taking it from
+    /// `reason` if the item reports a cancellation. Returns `None` for cancellations whose
+    /// reason is missing.
+    pub(crate) fn with_reason<R, F>(self, reason: F) -> Option<Progress<S, E, C, R>>
+    where
+        F: FnOnce() -> Option<R>,
+    {
+        let progress = match self {
+            Self::Ok(state) => Progress::Ok(state),
+            Self::SubStep {
+                state,
+                index,
+                label,
+            } => Progress::SubStep {
+                state,
+                index,
+                label,
+            },
+            Self::Warning { state, message } => Progress::Warning { state, message },
+            #[cfg(feature = "revert")]
+            Self::Revert(revert) => Progress::Revert(match revert {
+                RevertProgress::Cancelled { step, .. } => RevertProgress::Cancelled {
+                    reason: reason()?,
+                    step,
+                },
+                RevertProgress::Reverting { step, source } => {
+                    RevertProgress::Reverting { step, source }
+                }
+                RevertProgress::Reverted {
+                    source,
+                    compensated_states,
+                } => RevertProgress::Reverted {
+                    source,
+                    compensated_states,
+                },
+                RevertProgress::Failure { source, error } => {
+                    RevertProgress::Failure { source, error }
+                }
+            }),
+            #[cfg(not(feature = "revert"))]
+            Self::Failed { source } => Progress::Failed { source },
+            #[cfg(not(feature = "revert"))]
+            Self::Cancelled { step, .. } => Progress::Cancelled {
+                reason: reason()?,
+                step,
+            },
+            Self::Done => Progress::Done,
+            Self::Started(started) => Progress::Started(started),
+            Self::CancellationRequested { step } => Progress::CancellationRequested { step },
+            Self::Idle { state } => Progress::Idle { state },
+            Self::Waiting { state, reason } => Progress::Waiting { state, reason },
+            Self::Truncated => Progress::Truncated,
+        };
+
+        Some(progress)
+    }
+}
@@ -0,0 +1,150 @@
+use crate::rng::Rng;
+use std::{fmt, sync::Arc, time::Duration};
+
+/// A user-supplied backoff, computing the delay before a (zero-indexed) retry attempt
+type CustomBackoff = Arc<dyn Fn(u32) -> Duration + Send + Sync>;
+
+/// Configures how a `Streamline` retries transitions whose errors are categorized as
+/// `ErrorCategory::Retry` (see `State::categorize`), set through `Streamline::retry`. By default,
+/// transitions are retried up to 3 times without any delay between attempts.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetryPolicy {
+    max_retries: u32,
+    backoff: Backoff,
+    jitter: Jitter,
+    max_delay: Duration,
+}
+
+/// The default longest delay between two attempts
+const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(60 * 60);
+
+/// Randomization applied to the delay between retry attempts, so that many machines failing at
+/// the same time don't all retry at the same time too. The random number generator of the
+/// `Streamline` is used, which can be seeded through `Streamline::seeded` with the `testing`
+/// feature.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Jitter {
+    /// Wait for exactly the computed delay
+    None,
+    /// Wait for a random duration between zero and the computed delay. This spreads retries out
+    /// the most, and is a sensible choice for a fleet of machines retrying against the same
+    /// service.
+    Full,
+    /// Wait for half of the computed delay, plus a random duration up to the other half
+    Equal,
+}
+
+/// The delay between retry attempts
+#[derive(Clone)]
+enum Backoff {
+    None,
+    Fixed(Duration),
+    Exponential(Duration),
+    Custom(CustomBackoff),
+}
+
+impl fmt::Debug for Backoff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::None => f.write_str("None"),
+            Self::Fixed(delay) => f.debug_tuple("Fixed").field(delay).finish(),
+            Self::Exponential(base) => f.debug_tuple("Exponential").field(base).finish(),
+            Self::Custom(_) => f.write_str("Custom"),
+        }
+    }
+}
+
+impl PartialEq for Backoff {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::None, Self::None) => true,
+            (Self::Fixed(left), Self::Fixed(right)) => left == right,
+            (Self::Exponential(left), Self::Exponential(right)) => left == right,
+            (Self::Custom(left), Self::Custom(right)) => Arc::ptr_eq(left, right),
+            _ => false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Create a policy that retries a failed transition up to `max_retries` times, without any
+    /// delay between attempts
+    pub fn new(max_retries: u32) -> Self {
+        Self {
+            max_retries,
+            backoff: Backoff::None,
+            jitter: Jitter::None,
+            max_delay: DEFAULT_MAX_DELAY,
+        }
+    }
+
+    /// Wait for a fixed `delay` before every retry
+    pub fn fixed(mut self, delay: Duration) -> Self {
+        self.backoff = Backoff::Fixed(delay);
+        self
+    }
+
+    /// Wait for `base`, doubled after every attempt (up to `max_delay`), before every retry
+    pub fn exponential(mut self, base: Duration) -> Self {
+        self.backoff = Backoff::Exponential(base);
+        self
+    }
+
+    /// Compute the delay before every retry with a custom function of the (zero-indexed)
+    /// attempt. Jitter is still applied on top of the returned delay.
+    pub fn custom<F>(mut self, backoff: F) -> Self
+    where
+        F: Fn(u32) -> Duration + Send + Sync + 'static,
+    {
+        self.backoff = Backoff::Custom(Arc::new(backoff));
+        self
+    }
+
+    /// Randomize the delay before every retry. By default, no jitter is applied.
+    pub fn jitter(mut self, jitter: Jitter) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Cap the delay before every retry at `max_delay` (one hour by default), however large the
+    /// backoff grows (e.g. after many doubling exponential attempts). Jitter is applied on top of
+    /// the capped delay.
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// The maximum number of retries of a single transition
+    pub fn max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    /// The delay before the given (zero-indexed) retry attempt, with jitter applied
+    pub(crate) fn delay(&self, attempt: u32, rng: &mut Rng) -> Duration {
+        let delay = match &self.backoff {
+            Backoff::None => Duration::from_secs(0),
+            Backoff::Fixed(delay) => *delay,
+            Backoff::Exponential(base) => base
+                .checked_mul(2u32.saturating_pow(attempt))
+                .unwrap_or(Duration::MAX),
+            Backoff::Custom(backoff) => backoff(attempt),
+        }
+        .min(self.max_delay);
+
+        match self.jitter {
+            Jitter::None => delay,
+            Jitter::Full => rng.duration_between(Duration::from_secs(0), delay),
+            Jitter::Equal => {
+                let half = delay / 2;
+
+                half + rng.duration_between(Duration::from_secs(0), delay - half)
+            }
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new(3)
+    }
+}
@@ -0,0 +1,42 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::Duration,
+};
+
+/// A small, seedable pseudo-random number generator (xorshift64*) used to pick jitter delays
+pub(crate) struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Create a generator that always produces the same sequence for the same `seed`
+    pub(crate) fn seeded(seed: u64) -> Self {
+        // xorshift generators get stuck on a zero state
+        let state = if seed == 0 {
+            0x9E37_79B9_7F4A_7C15
+        } else {
+            seed
+        };
+
+        Self { state }
+    }
+
+    /// Pick a duration between `min` and `max` (inclusive)
+    pub(crate) fn duration_between(&mut self, min: Duration, max: Duration) -> Duration {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+
+        let random = self.state.wrapping_mul(0x2545_F491_4F6C_DD1D);
+        let unit = (random >> 11) as f64 / (1u64 << 53) as f64;
+
+        min + max.saturating_sub(min).mul_f64(unit)
+    }
+}
+
+impl Default for Rng {
+    fn default() -> Self {
+        Self::seeded(RandomState::new().build_hasher().finish())
+    }
+}
@@ -0,0 +1,115 @@
+use crate::{progress::Progress, state::State, streamline::Streamline};
+use futures::{
+    channel::oneshot::{self, Sender},
+    future,
+    stream::{FusedStream, LocalBoxStream},
+    Stream, StreamExt,
+};
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// A Stream of states returned from `Streamline::run_with_shutdown`, which can be shut down
+/// gracefully part-way through with `shutdown`. Dropping it before it has finished (without
+/// calling `shutdown`) skips any async cleanup, like dropping any other running `Streamline`,
+/// and logs a warning in debug builds with the `log` feature.
+pub struct RunningStreamline<C, E, S>
+where
+    S: State<Context = C, Error = E>,
+{
+    stream: LocalBoxStream<'static, Progress<S, E, C>>,
+    shutdown: Option<Sender<()>>,
+    finished: bool,
+}
+
+impl<C, E, S> RunningStreamline<C, E, S>
+where
+    S: State<Context = C, Error = E>,
+{
+    /// Shut the `Streamline` down gracefully: cancel it (as if through a `Cancel` handle) and
+    /// drive the resulting reversion to completion, discarding every remaining state. This gives
+    /// async `revert`s and compensations a chance to run, since `Drop` can only run synchronous
+    /// cleanup (like the `finally` callback). Any delay or pause in progress is cut short, and a
+    /// `Streamline` that has already finished moving forward only finishes what it was doing.
+    pub async fn shutdown(mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            shutdown.send(()).ok();
+        }
+
+        while self.stream.next().await.is_some() {}
+
+        self.finished = true;
+    }
+}
+
+impl<C, E, S> Stream for RunningStreamline<C, E, S>
+where
+    S: State<Context = C, Error = E>,
+{
+    type Item = Progress<S, E, C>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let next = self.stream.poll_next_unpin(cx);
+
+        if let Poll::Ready(None) = next {
+            self.finished = true;
+        }
+
+        next
+    }
+}
+
+impl<C, E, S> FusedStream for RunningStreamline<C, E, S>
+where
+    S: State<Context = C, Error = E>,
+{
+    fn is_terminated(&self) -> bool {
+        self.finished
+    }
+}
+
+impl<C, E, S> Drop for RunningStreamline<C, E, S>
+where
+    S: State<Context = C, Error = E>,
+{
+    fn drop(&mut self) {
+        #[cfg(all(debug_assertions, feature = "log"))]
+        if !self.finished {
+            log::warn!(
+                "a running Streamline was dropped without calling `shutdown`, skipping its async \
+                 cleanup"
+            );
+        }
+    }
+}
+
+impl<C, E, S> Streamline<C, E, S>
+where
+    S: State<Context = C, Error = E> + 'static,
+    C: 'static,
+    E: 'static,
+{
+    /// Generate a Stream of states (like `run`) that can be shut down gracefully through
+    /// `RunningStreamline::shutdown` once it has started. Shutting down is combined with any
+    /// other cancellation source (e.g. from `preemptible`) rather than replacing it.
+    pub fn run_with_shutdown(self) -> RunningStreamline<C, E, S> {
+        let (shutdown, signal) = oneshot::channel::<()>();
+
+        let stream = self
+            .or_cancel_on(async move {
+                // dropping the Sender without shutting down should not cancel anything
+                if signal.await.is_err() {
+                    future::pending::<()>().await;
+                }
+            })
+            .run()
+            .boxed_local();
+
+        RunningStreamline {
+            stream,
+            shutdown: Some(shutdown),
+            finished: false,
+        }
+    }
+}
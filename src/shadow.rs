@@ -0,0 +1,58 @@
+use crate::{progress::Progress, state::State, streamline::Streamline};
+use futures::{
+    future,
+    stream::{self, Stream, StreamExt},
+};
+
+/// A mismatch between the states emitted by the two `Streamline`s run by `shadow`
+#[derive(Debug)]
+pub struct Divergence<S, E, C>
+where
+    S: State<Context = C, Error = E>,
+{
+    /// the position of the mismatched states within their Streams
+    pub index: usize,
+    /// the state emitted by the old `Streamline`, or `None` if it had already finished
+    pub old: Option<Progress<S, E, C>>,
+    /// the state emitted by the new `Streamline`, or `None` if it had already finished
+    pub new: Option<Progress<S, E, C>>,
+}
+
+/// Run two `Streamline`s over the same states in lockstep (e.g. the old and new versions of a
+/// refactored `State` implementation), emitting the states of the old `Streamline` for as long
+/// as both emit identical states. As soon as they diverge (including one finishing before the
+/// other), a single `Divergence` describing the mismatch is emitted instead, and the Stream
+/// ends.
+///
+/// Both `Streamline`s really run, so each needs its own context: build each of them with a
+/// separate clone of the context rather than a `SharedContext`, so that they can't observe each
+/// other's changes. Any side effects outside of the context happen twice.
+pub fn shadow<C, E, S>(
+    old: Streamline<C, E, S>,
+    new: Streamline<C, E, S>,
+) -> impl Stream<Item = Result<Progress<S, E, C>, Divergence<S, E, C>>>
+where
+    S: State<Context = C, Error = E>,
+    Progress<S, E, C>: PartialEq,
+{
+    let streams = (Box::pin(old.run()), Box::pin(new.run()));
+
+    stream::unfold(
+        (streams, 0, false),
+        |((mut old, mut new), index, diverged)| async move {
+            if diverged {
+                return None;
+            }
+
+            let item = match future::join(old.next(), new.next()).await {
+                (None, None) => return None,
+                (Some(old), Some(new)) if old == new => Ok(old),
+                (old, new) => Err(Divergence { index, old, new }),
+            };
+
+            let diverged = item.is_err();
+
+            Some((item, ((old, new), index + 1, diverged)))
+        },
+    )
+}
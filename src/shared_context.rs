@@ -0,0 +1,63 @@
+use futures::lock::{Mutex, MutexGuard, OwnedMutexGuard};
+use std::sync::Arc;
+
+/// A `Context` shared between several `Streamline`s (e.g. the stages of a multi-stage workflow)
+/// through `Streamline::share_context`. Each `Streamline` holds the lock for the duration of
+/// every transition (except while it waits, e.g. for `Streamline::pause_when`), so their
+/// transitions never overlap, and the context outlives any single `Streamline`. Cloning a
+/// `SharedContext` shares the same inner value.
+#[derive(Debug)]
+pub struct SharedContext<C> {
+    inner: Arc<Mutex<Option<C>>>,
+}
+
+impl<C> Clone for SharedContext<C> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<C> SharedContext<C> {
+    /// Wrap a context for sharing between `Streamline`s
+    pub fn new(context: C) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Some(context))),
+        }
+    }
+
+    /// Run a closure with exclusive access to the context, waiting for any in-flight transition
+    /// to complete first
+    pub async fn with<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut C) -> R,
+    {
+        let mut context = self.inner.lock().await;
+
+        f(context
+            .as_mut()
+            .expect("shared context was lost during a transition"))
+    }
+
+    /// Take the context back out of the `SharedContext`, returning the `SharedContext` again if
+    /// it is still shared with other handles (or `Streamline`s)
+    pub fn try_unwrap(self) -> Result<C, Self> {
+        match Arc::try_unwrap(self.inner) {
+            Ok(inner) => Ok(inner
+                .into_inner()
+                .expect("shared context was lost during a transition")),
+            Err(inner) => Err(Self { inner }),
+        }
+    }
+
+    /// Wait for exclusive access to the context slot, for as long as the guard is held
+    pub(crate) async fn lock_owned(&self) -> OwnedMutexGuard<Option<C>> {
+        self.inner.clone().lock_owned().await
+    }
+
+    /// Get exclusive access to the context slot, if it isn't currently locked
+    pub(crate) fn try_lock(&self) -> Option<MutexGuard<'_, Option<C>>> {
+        self.inner.try_lock()
+    }
+}
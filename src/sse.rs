@@ -0,0 +1,92 @@
+#[cfg(feature = "revert")]
+use crate::progress::RevertProgress;
+use crate::{progress::Progress, state::State, streamline::Streamline};
+use futures::{stream::FusedStream, StreamExt};
+use std::fmt::Display;
+
+impl<C, E, S> Streamline<C, E, S>
+where
+    S: State<Context = C, Error = E> + Display,
+    E: Display,
+{
+    /// Generate a Stream of Server-Sent Events frames describing each state, consuming the
+    /// `Streamline`, for piping directly into an SSE response body. States and errors are
+    /// described through their `Display` implementations. Every frame is an `event:` line naming
+    /// the phase, followed by a `data:` line for every line of the description, and a blank line:
+    ///
+    /// - `ok` with the state
+    /// - `sub_step` with `{state}: {label}`
+    /// - `warning` with `{state}: {message}`
+    /// - `cancelled` with the state whose transition was cancelled for a reason
+    /// - `reverting` with the state being reverted
+    /// - `reverted` with the error that triggered reversion (empty for cancellations)
+    /// - `failure` with the error that caused reversion to fail
+    /// - `failed` with the error that stopped the `Streamline` (without the `revert` feature)
+    /// - `done` and `started`, with empty data
+    /// - `cancellation_requested` with the state whose transition was cancelled
+    /// - `idle` with the state whose transition is waiting
+    /// - `waiting` with `{state}: {reason}`
+    /// - `truncated`, with empty data
+    pub fn run_sse(self) -> impl FusedStream<Item = String> {
+        self.run().map(|progress| to_sse_frame(&progress))
+    }
+}
+
+fn to_sse_frame<S, E, C>(progress: &Progress<S, E, C>) -> String
+where
+    S: State<Context = C, Error = E> + Display,
+    E: Display,
+{
+    let (event, data) = describe(progress);
+
+    let mut frame = format!("event: {}\n", event);
+
+    // every line of the data needs its own field, since SSE fields can't contain newlines
+    for line in data.split('\n') {
+        frame.push_str("data: ");
+        frame.push_str(line.trim_end_matches('\r'));
+        frame.push('\n');
+    }
+
+    frame.push('\n');
+
+    frame
+}
+
+/// Describe a state through the `Display` implementations of states and errors, as the name of
+/// its phase and a description that may be empty (see `Streamline::run_sse`)
+pub(crate) fn describe<S, E, C>(progress: &Progress<S, E, C>) -> (&'static str, String)
+where
+    S: State<Context = C, Error = E> + Display,
+    E: Display,
+{
+    match progress {
+        Progress::Ok(state) => ("ok", state.to_string()),
+        Progress::SubStep { state, label, .. } => ("sub_step", format!("{}: {}", state, label)),
+        Progress::Warning { state, message } => ("warning", format!("{}: {}", state, message)),
+        #[cfg(feature = "revert")]
+        Progress::Revert(RevertProgress::Cancelled { step, .. }) => ("cancelled", step.to_string()),
+        #[cfg(feature = "revert")]
+        Progress::Revert(RevertProgress::Reverting { step, .. }) => ("reverting", step.to_string()),
+        #[cfg(feature = "revert")]
+        Progress::Revert(RevertProgress::Reverted { source, .. }) => (
+            "reverted",
+            source.as_ref().map(ToString::to_string).unwrap_or_default(),
+        ),
+        #[cfg(feature = "revert")]
+        Progress::Revert(RevertProgress::Failure { error, .. }) => ("failure", error.to_string()),
+        #[cfg(not(feature = "revert"))]
+        Progress::Failed { source } => (
+            "failed",
+            source.as_ref().map(ToString::to_string).unwrap_or_default(),
+        ),
+        #[cfg(not(feature = "revert"))]
+        Progress::Cancelled { step, .. } => ("cancelled", step.to_string()),
+        Progress::Done => ("done", String::new()),
+        Progress::Started(_) => ("started", String::new()),
+        Progress::CancellationRequested { step } => ("cancellation_requested", step.to_string()),
+        Progress::Idle { state } => ("idle", state.to_string()),
+        Progress::Waiting { state, reason } => ("waiting", format!("{}: {}", state, reason)),
+        Progress::Truncated => ("truncated", String::new()),
+    }
+}
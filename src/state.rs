@@ -1,4 +1,22 @@
+use crate::step_policy::StepPolicy;
 use async_trait::async_trait;
+use std::time::Instant;
+
+/// The way a `Streamline` handles an error returned from `State::next`, as decided by
+/// `State::categorize`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ErrorCategory {
+    /// Run the failed transition again, according to the `Streamline`'s `RetryPolicy`. Once the
+    /// policy's retries are exhausted, the error is handled like `ErrorCategory::Revert`.
+    Retry,
+    /// Start the reversion process (or end the `Streamline` with `Progress::Failed`, without the
+    /// `revert` feature)
+    Revert,
+    /// End the `Streamline` immediately without reverting any previous states, with a
+    /// `RevertProgress::Failure` carrying the error (or `Progress::Failed`, without the `revert`
+    /// feature)
+    Abort,
+}
 
 /// The `State` trait defines the way that a `Streamline` progresses to (or from) the next state.
 #[async_trait(?Send)]
@@ -15,9 +33,156 @@ pub trait State: Clone + PartialEq {
     /// `Ok(Some(Self))` is returned, the stream continues to the next iteration of `next`
     async fn next(&self, context: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error>;
 
+    /// Derives the next state like `next`, given the state that preceded this one, for
+    /// transitions that depend on where the `Streamline` came from. `Streamline`s always call
+    /// this method (through `next_with_history`) rather than `next`, with `previous` set to
+    /// `None` for the initial state (including states that a `Streamline` was started from with
+    /// `from_state` or `replay_from`). By default, `next_from` ignores `previous` and delegates
+    /// to `next`.
+    async fn next_from(
+        &self,
+        _previous: Option<&Self>,
+        context: Option<&mut Self::Context>,
+    ) -> Result<Option<Self>, Self::Error> {
+        self.next(context).await
+    }
+
+    /// Derives the next state like `next`, given every state visited before this one in the
+    /// current run, oldest first, for transitions that depend on the whole path taken so far
+    /// (e.g. to avoid revisiting states). `Streamline`s always call this method rather than
+    /// `next`, but only keep the full history once enabled through `Streamline::track_history`:
+    /// otherwise, `history` holds at most the previous state. By default, `next_with_history`
+    /// delegates to `next_from` with the last state of `history`.
+    async fn next_with_history(
+        &self,
+        history: &[Self],
+        context: Option<&mut Self::Context>,
+    ) -> Result<Option<Self>, Self::Error> {
+        self.next_from(history.last(), context).await
+    }
+
     /// Handles the mapping between a state and its previous state in the case of reversion on
-    /// `Err` from `next()`. By default, `revert` simply ends the `Streamline`
+    /// `Err` from `next()`. By default, `revert` simply ends the `Streamline`. Only available with
+    /// the `revert` feature.
+    #[cfg(feature = "revert")]
     async fn revert(&self, _context: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
         Ok(None)
     }
+
+    /// Declares that this state's `revert` is independent of the reversion of every other state
+    /// and of the context, so that it can run concurrently with other independent reverts in
+    /// `Streamline`s built with `Streamline::parallel_revert`. By default, reverts are sequential.
+    /// Only available with the `revert` feature.
+    #[cfg(feature = "revert")]
+    fn independent_revert(&self) -> bool {
+        false
+    }
+
+    /// Transforms the error returned from this state's `next` once, as reversion starts, before
+    /// it is shared between every `RevertProgress` item of the reversion. Useful for replacing
+    /// errors with large payloads with a lightweight summary, so that the payload isn't kept
+    /// alive for the whole of a lengthy rollback. By default, errors are kept as they are. Only
+    /// available with the `revert` feature.
+    #[cfg(feature = "revert")]
+    fn summarize_error(&self, error: Self::Error) -> Self::Error {
+        error
+    }
+
+    /// The timeout and retry configuration for this state's forward transition, overriding the
+    /// `Streamline`'s own policy (see `Streamline::step_policy`) as a whole. By default, states
+    /// use the `Streamline`'s policy.
+    fn step_policy(&self) -> Option<StepPolicy> {
+        None
+    }
+
+    /// Labels for the intermediate steps of this state's transition, reported as
+    /// `Progress::SubStep` items after a successful `next` for more granular progress reporting.
+    /// The state still only has a single `next` transition. By default, states have no sub-steps.
+    fn sub_steps(&self) -> Vec<String> {
+        vec![]
+    }
+
+    /// Non-fatal warnings raised while transitioning into this state, emitted as
+    /// `Progress::Warning` items before the state itself without triggering a reversion. Called
+    /// once on every state returned from `next`. By default, states raise no warnings.
+    fn take_warnings(&self) -> Vec<String> {
+        vec![]
+    }
+
+    /// A stable key identifying this state across runs, used by idempotent `Streamline`s to skip
+    /// states that have already run. By default, states have no key and are never skipped.
+    fn idempotency_key(&self) -> Option<String> {
+        None
+    }
+
+    /// The states that this state may transition to through `next`, in order of likelihood. This
+    /// is a static declaration used only for planning and analysis (e.g. by
+    /// `Streamline::estimated_remaining_cost`); it is never used to drive or validate the
+    /// `Streamline` itself. By default, states declare no transitions.
+    fn transitions(&self) -> Vec<Self> {
+        vec![]
+    }
+
+    /// The states that this state may transition back to through `revert`, as a static
+    /// declaration used only for analysis (e.g. by `validate_graph`), like `transitions`. By
+    /// default, states declare no revert transitions. Only available with the `revert` feature.
+    #[cfg(feature = "revert")]
+    fn revert_transitions(&self) -> Vec<Self> {
+        vec![]
+    }
+
+    /// An estimate of the relative cost of running this state's `next` transition, for use by
+    /// external schedulers. By default, every state costs `1`.
+    fn cost(&self) -> u64 {
+        1
+    }
+
+    /// A moment to wait for before running this state's `next` transition (e.g. to retry in five
+    /// minutes), turning a `Streamline` into a simple scheduler. Cancellation requests and
+    /// deadlines still interrupt the wait (though debounced cancellations are only observed once
+    /// it is over). Delays are timed through `futures-timer`, which runs its own timer thread, so
+    /// no particular async runtime is required. By default, states transition immediately.
+    fn delay_until(&self) -> Option<Instant> {
+        None
+    }
+
+    /// A description of the external resource (e.g. a lock or a rate-limit slot) that this
+    /// state's `next` transition is about to block on, if any. When there is one, the
+    /// `Streamline` emits a `Progress::Waiting` marker with it before running `next`, so
+    /// consumers can tell a blocked machine from a busy one. By default, states don't wait.
+    fn waiting_on(&self) -> Option<String> {
+        None
+    }
+
+    /// Whether cancellation requests may interrupt the `Streamline` right before this state's
+    /// `next` transition, for protecting critical sections (e.g. the middle of a multi-step
+    /// atomic operation) from being left half-done. Requests observed while the current state
+    /// isn't cancellable are deferred rather than dropped: the `Streamline` keeps moving forward
+    /// until it reaches a cancellable state, then stops and reverts from there as usual
+    /// (reverting the non-cancellable states along the way, too). Waiting on `delay_until` or
+    /// `pause_when` is not interrupted either, though deadlines still apply. By default, every
+    /// state is cancellable.
+    fn is_cancellable(&self) -> bool {
+        true
+    }
+
+    /// Declares whether this state's `next` transition has side effects outside of the context
+    /// (e.g. calls to external services), for use in static reviews through
+    /// `Streamline::side_effecting_states`. By default, states are assumed to have side effects.
+    fn has_side_effects(&self) -> bool {
+        true
+    }
+
+    /// Declares that this state is terminal, i.e. that its `next` would always return `Ok(None)`.
+    /// Final states end the `Streamline` as soon as they are reached, without calling `next`
+    /// (or checking for cancellation). By default, states are not final.
+    fn is_final(&self) -> bool {
+        false
+    }
+
+    /// Decides how an error returned from this state's `next` is handled: retried, reverted, or
+    /// aborted without reverting. By default, every error triggers a reversion.
+    fn categorize(&self, _error: &Self::Error) -> ErrorCategory {
+        ErrorCategory::Revert
+    }
 }
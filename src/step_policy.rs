@@ -0,0 +1,26 @@
+use crate::retry::RetryPolicy;
+use std::{error::Error, fmt, time::Duration};
+
+/// Combined timeout and retry configuration for forward transitions, set for every state through
+/// `Streamline::step_policy` or for individual states through `State::step_policy`. Each attempt
+/// at a transition is limited to `timeout` (if one is set), and attempts that time out are
+/// retried according to `retries`, along with any errors categorized as `ErrorCategory::Retry`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StepPolicy {
+    /// the longest that a single attempt at a transition can take, if any
+    pub timeout: Option<Duration>,
+    /// the retries allowed after errors and timeouts
+    pub retries: RetryPolicy,
+}
+
+/// The error used when an attempt at a transition takes longer than its `StepPolicy` allows
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StepTimedOut;
+
+impl fmt::Display for StepTimedOut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("transition timed out")
+    }
+}
+
+impl Error for StepTimedOut {}
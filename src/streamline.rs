@@ -1,13 +1,83 @@
+#[cfg(feature = "log")]
+use crate::sse;
+#[cfg(feature = "opentelemetry")]
+use crate::telemetry::Telemetry;
+#[cfg(feature = "timestamps")]
+use crate::timestamp;
+#[cfg(feature = "revert")]
 use crate::{
-    cancel::Cancel,
-    progress::{Progress, RevertProgress},
-    state::State,
+    abort::{self, Abort, AbortRevert, RevertAborted},
+    batch::{self, BatchRunner, BatchState, PartialBatch},
+    compensation::CompensationRegistry,
+    outcome::{Outcome, RunReport},
+    progress::RevertProgress,
+};
+use crate::{
+    breaker::{Breaker, CircuitBreaker, CircuitOpen},
+    cancel::{self, Cancel, CancelReceiver, Cancellation, DebouncedCancel},
+    control::ControlHandle,
+    fraction::{self, Fraction},
+    idempotency::{ContextIdempotency, ContextRecord, IdempotencyStore},
+    log_capture::{LogCapture, LogLine},
+    monotonic::{self, NotMonotonic},
+    outcome::TerminalOutcome,
+    progress::{Phase, Progress},
+    retry::RetryPolicy,
+    rng::Rng,
+    shared_context::SharedContext,
+    state::{ErrorCategory, State},
+    step_policy::{StepPolicy, StepTimedOut},
+    termination::UnexpectedTermination,
+    trace::TraceHandle,
+    versioned::{ContextModified, VersionCheck, Versioned},
+    wal::WriteAheadLog,
 };
 use futures::{
-    channel::oneshot::{self, Receiver},
-    stream, Stream,
+    channel::{mpsc, oneshot::Receiver},
+    future::{self, Either},
+    lock::OwnedMutexGuard,
+    stream::{self, FusedStream},
+    FutureExt, StreamExt,
 };
-use std::sync::Arc;
+#[cfg(feature = "timestamps")]
+use std::time::SystemTime;
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Debug,
+    future::Future,
+    panic::{self, AssertUnwindSafe},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+#[cfg(feature = "tokio")]
+use tokio::sync::Semaphore;
+
+/// A callback run with the context when entering a particular state
+type StateHook<C> = Box<dyn Fn(&mut Option<C>)>;
+
+/// A callback that logs every emitted state, given the name of the `Streamline`
+#[cfg(feature = "log")]
+type ProgressLogger<S, E, C> = Box<dyn Fn(&str, &Progress<S, E, C>)>;
+
+/// A cleanup callback run once the `Streamline` has finished (or been dropped)
+type Finalizer<C> = Box<dyn FnOnce(TerminalOutcome, Option<&mut C>)>;
+
+/// A diagnostic callback run with the state whose transition panicked
+type PanicHook<S> = Box<dyn Fn(&S)>;
+
+/// A check run on every forward transition, from the current state to the next one
+type TransitionCheck<S, E> = fn(&S, &S) -> Result<(), E>;
+
+/// A transformation applied to errors that trigger a reversion
+#[cfg(feature = "revert")]
+type ErrorMapper<S, E> = Box<dyn Fn(E, &S) -> E>;
+
+/// A predicate on the context that pauses forward progress while it holds
+type PausePredicate<C> = Box<dyn Fn(&C) -> bool>;
+
+/// A predicate marking the states past which errors trigger a reversion
+#[cfg(feature = "revert")]
+type RevertPredicate<S> = Box<dyn Fn(&S) -> bool>;
 
 /// Streamlines represent the streams of states configured for a particular Context, Error type,
 /// and `State`-implementing type
@@ -15,9 +85,70 @@ pub struct Streamline<C, E, S>
 where
     S: State<Context = C, Error = E>,
 {
-    cancellation_handle: Option<Receiver<()>>,
+    #[cfg(feature = "revert")]
+    abort_handle: Option<Abort<E>>,
+    #[cfg(feature = "revert")]
+    batch_runner: Option<BatchRunner<S, C, E>>,
+    #[cfg(feature = "revert")]
+    batched: usize,
+    breaker: Option<Breaker<E>>,
+    cancellation_handle: Option<Cancellation>,
+    #[cfg(feature = "revert")]
+    compensated: Vec<S>,
+    #[cfg(feature = "revert")]
+    compensations: Option<CompensationRegistry<S, C, E>>,
+    completion_marker: bool,
     context: Option<C>,
-    current: Progress<S, E, C>,
+    context_record: Option<ContextRecord<C, S>>,
+    control: Option<ControlHandle<S>>,
+    current: Option<Progress<S, E, C>>,
+    deadline: Option<Instant>,
+    emissions: usize,
+    #[cfg(feature = "revert")]
+    error_mapper: Option<ErrorMapper<S, E>>,
+    finalizer: Option<Finalizer<C>>,
+    #[cfg(feature = "revert")]
+    history: Vec<S>,
+    idempotency_store: Option<Box<dyn IdempotencyStore<S>>>,
+    idle: Option<S>,
+    idle_marker: bool,
+    #[cfg(feature = "testing")]
+    jitter: Option<(Duration, Duration)>,
+    max_emissions: Option<usize>,
+    #[cfg(feature = "revert")]
+    max_parallelism: usize,
+    metadata: Arc<HashMap<String, String>>,
+    monotonic_check: Option<TransitionCheck<S, E>>,
+    name: Option<String>,
+    panic_hook: Option<PanicHook<S>>,
+    #[cfg(feature = "revert")]
+    parallel_revert: bool,
+    #[cfg(feature = "revert")]
+    partial_batch: Option<PartialBatch<C, E>>,
+    pause: Option<(PausePredicate<C>, Duration)>,
+    pending: VecDeque<Progress<S, E, C>>,
+    #[cfg(feature = "revert")]
+    point_of_no_return: Option<(RevertPredicate<S>, bool)>,
+    previous: Option<S>,
+    #[cfg(feature = "log")]
+    progress_logger: Option<ProgressLogger<S, E, C>>,
+    resuming: bool,
+    retry_policy: RetryPolicy,
+    rng: Rng,
+    #[cfg(feature = "tokio")]
+    semaphore: Option<Arc<Semaphore>>,
+    shared_context: Option<SharedContext<C>>,
+    shared_guard: Option<OwnedMutexGuard<Option<C>>>,
+    start_marker: bool,
+    state_hooks: Vec<(S, StateHook<C>)>,
+    step_timeout: Option<Duration>,
+    #[cfg(feature = "opentelemetry")]
+    telemetry: Option<Telemetry>,
+    termination_error: Option<fn(UnexpectedTermination) -> E>,
+    timeout_error: Option<fn(StepTimedOut) -> E>,
+    version_check: Option<VersionCheck<C, E>>,
+    visited: Option<Vec<S>>,
+    wal: Option<Box<dyn WriteAheadLog<S, E>>>,
 }
 
 impl<C, E, S> Streamline<C, E, S>
@@ -26,10 +157,108 @@ where
 {
     /// Create a `Streamline` from an initial state
     pub fn build(state: S) -> Self {
+        Self::from_progress(Some(Progress::from(state)))
+    }
+
+    /// Create a `Streamline` that starts from any state, not necessarily the usual initial state
+    /// of the machine (e.g. to reproduce a bug from a state observed in a previous run). This is
+    /// the same as `build`, which already accepts any state.
+    pub fn from_state(state: S) -> Self {
+        Self::build(state)
+    }
+
+    /// Start a fresh run forward from the state at `index` of a previous run's `history` (e.g.
+    /// as collected from `run`). Items describing a state (including sub-steps, warnings,
+    /// cancellations, and reversion steps) replay from that state. Returns `None` if `index` is
+    /// out of bounds, or if the item at `index` doesn't describe a state (e.g. a final
+    /// `RevertProgress::Reverted`). Nothing else is carried over from the previous run, so the
+    /// context and any other configuration need to be set up again.
+    pub fn replay_from(history: &[Progress<S, E, C>], index: usize) -> Option<Self> {
+        let state = match history.get(index)? {
+            Progress::Ok(state)
+            | Progress::SubStep { state, .. }
+            | Progress::Warning { state, .. }
+            | Progress::CancellationRequested { step: state }
+            | Progress::Idle { state }
+            | Progress::Waiting { state, .. } => state,
+            #[cfg(feature = "revert")]
+            Progress::Revert(
+                RevertProgress::Cancelled { step, .. } | RevertProgress::Reverting { step, .. },
+            ) => step,
+            #[cfg(not(feature = "revert"))]
+            Progress::Cancelled { step, .. } => step,
+            _ => return None,
+        };
+
+        Some(Self::from_state(state.clone()))
+    }
+
+    fn from_progress(current: Option<Progress<S, E, C>>) -> Self {
         Self {
+            #[cfg(feature = "revert")]
+            abort_handle: None,
+            #[cfg(feature = "revert")]
+            batch_runner: None,
+            #[cfg(feature = "revert")]
+            batched: 0,
+            breaker: None,
             cancellation_handle: None,
+            #[cfg(feature = "revert")]
+            compensated: vec![],
+            #[cfg(feature = "revert")]
+            compensations: None,
+            completion_marker: false,
             context: None,
-            current: Progress::from(state),
+            context_record: None,
+            control: None,
+            current,
+            deadline: None,
+            emissions: 0,
+            #[cfg(feature = "revert")]
+            error_mapper: None,
+            finalizer: None,
+            #[cfg(feature = "revert")]
+            history: vec![],
+            idempotency_store: None,
+            idle: None,
+            idle_marker: false,
+            #[cfg(feature = "testing")]
+            jitter: None,
+            max_emissions: None,
+            #[cfg(feature = "revert")]
+            max_parallelism: 1,
+            metadata: Arc::default(),
+            monotonic_check: None,
+            name: None,
+            panic_hook: None,
+            #[cfg(feature = "revert")]
+            parallel_revert: false,
+            #[cfg(feature = "revert")]
+            partial_batch: None,
+            pause: None,
+            pending: VecDeque::new(),
+            #[cfg(feature = "revert")]
+            point_of_no_return: None,
+            previous: None,
+            #[cfg(feature = "log")]
+            progress_logger: None,
+            resuming: false,
+            retry_policy: RetryPolicy::default(),
+            rng: Rng::default(),
+            #[cfg(feature = "tokio")]
+            semaphore: None,
+            shared_context: None,
+            shared_guard: None,
+            start_marker: false,
+            state_hooks: vec![],
+            step_timeout: None,
+            #[cfg(feature = "opentelemetry")]
+            telemetry: None,
+            termination_error: None,
+            timeout_error: None,
+            version_check: None,
+            visited: None,
+            wal: None,
         }
     }
 
@@ -40,91 +269,2108 @@ where
         self
     }
 
-    /// Generate a Stream of states, consuming the `Streamline`
-    pub fn run(self) -> impl Stream<Item = Progress<S, E, C>> {
-        stream::unfold(Some(self), Self::reduce)
+    /// Modify the context set through `context`, for building it up in layers (e.g. applying
+    /// environment variables and then command-line flags over a base configuration). This is a
+    /// no-op if no context has been set yet, including contexts attached through
+    /// `share_context`.
+    pub fn context_with<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(&mut C),
+    {
+        if let Some(context) = &mut self.context {
+            f(context);
+        }
+
+        self
     }
 
-    /// Return a Stream of states and a cancellation handle
-    pub fn run_preemptible(mut self) -> (impl Stream<Item = Progress<S, E, C>>, Cancel) {
-        let (sender, receiver) = oneshot::channel::<()>();
-
-        self.cancellation_handle = Some(receiver);
-
-        (self.run(), Cancel::from(sender))
-    }
-
-    async fn reduce(state_machine: Option<Self>) -> Option<(Progress<S, E, C>, Option<Self>)> {
-        if let Some(mut state_machine) = state_machine {
-            let context = state_machine.context.as_mut();
-            let next_state = match &state_machine.current {
-                Progress::Ok(inner) => {
-                    let cancellation_handle = match state_machine.cancellation_handle {
-                        Some(_) => std::mem::take(&mut state_machine.cancellation_handle),
-                        None => None,
-                    };
-
-                    // Before moving to the next state, check that the current
-                    // streamline hasn't been cancelled externally
-                    let cancelled_state = match cancellation_handle {
-                        Some(mut reciever) => match reciever.try_recv() {
-                            Ok(Some(_)) => Some(Progress::Revert(RevertProgress::Reverting {
-                                step: inner.clone(),
-                                source: None,
-                            })),
-                            _ => {
-                                // replace the original receiver if one existed in the first place
-                                std::mem::replace(
-                                    &mut state_machine.cancellation_handle,
-                                    Some(reciever),
-                                );
-
-                                None
-                            }
-                        },
-                        _ => None,
-                    };
-
-                    if cancelled_state.is_some() {
-                        cancelled_state
-                    } else {
-                        match inner.next(context).await {
-                            Ok(None) => None,
-                            Ok(Some(next)) => Some(Progress::Ok(next)),
-                            Err(source) => Some(Progress::Revert(RevertProgress::Reverting {
-                                step: inner.clone(),
-                                source: Some(Arc::new(source)),
-                            })),
-                        }
-                    }
+    /// Attach metadata (e.g. request or trace IDs) for correlating this `Streamline`'s progress
+    /// with external systems, emitted alongside every state by `run_with_metadata`. Metadata is
+    /// kept separate from states and the context, and replaces any metadata attached before.
+    pub fn with_metadata(mut self, metadata: HashMap<String, String>) -> Self {
+        self.metadata = Arc::new(metadata);
+
+        self
+    }
+
+    /// Use a context shared with other `Streamline`s (e.g. the other stages of a multi-stage
+    /// workflow) in place of an owned one, so that every `Streamline` sees the changes made by
+    /// the others. The context is locked for the whole of each transition (including any hooks),
+    /// and is unlocked between transitions, so transitions of `Streamline`s sharing a context
+    /// never overlap, and a slow transition holds up every other `Streamline`. The context is
+    /// also unlocked while a transition waits without running any code of its own (for
+    /// `pause_when`, `State::delay_until`, retry backoff, jitter, or a semaphore permit), so that
+    /// other `Streamline`s and `SharedContext::with` can use it (e.g. to end a pause) in the
+    /// meantime, and is locked again (with any changes) before the transition moves on. Methods
+    /// that read the context between transitions (e.g. `run_with_snapshots` and `peek_next`)
+    /// and checkpoints only see owned contexts.
+    pub fn share_context(mut self, shared: SharedContext<C>) -> Self {
+        self.shared_context = Some(shared);
+
+        self
+    }
+
+    /// Emit a final `Progress::Done` marker after the last state of the `Streamline`, whether it
+    /// completed, reverted, or failed to revert. Useful for telling where one `Streamline`'s
+    /// progress ends when merging several Streams together.
+    pub fn emit_completion_marker(mut self) -> Self {
+        self.completion_marker = true;
+
+        self
+    }
+
+    /// Emit a `Progress::Started` marker, timestamped with the moment the `Streamline` starts
+    /// running, before its initial state. This changes the first item seen by consumers, so it is
+    /// off by default. Subtracting the start time from a later timestamp gives the total
+    /// duration of the `Streamline`.
+    pub fn emit_start_marker(mut self) -> Self {
+        self.start_marker = true;
+
+        self
+    }
+
+    /// Emit a `Progress::Idle` marker whenever the `Streamline` is about to sit idle, waiting to
+    /// run a state's transition until its `State::delay_until` (e.g. so that a supervisor can
+    /// reclaim resources in the meantime). Markers are emitted right after the waiting state,
+    /// before the wait starts. This adds items to the Stream, so it is off by default.
+    pub fn emit_idle_marker(mut self) -> Self {
+        self.idle_marker = true;
+
+        self
+    }
+
+    /// Name the `Streamline` for diagnostics. With the `opentelemetry` feature enabled, the name is
+    /// used as the name of the span covering the whole `Streamline`, and as the prefix of every
+    /// transition's span name. Unnamed `Streamline`s are called `streamline`.
+    pub fn with_name<N>(mut self, name: N) -> Self
+    where
+        N: Into<String>,
+    {
+        self.name = Some(name.into());
+
+        self
+    }
+
+    /// Log every emitted state through the `log` crate, prefixed with the name of the `Streamline`
+    /// (see `with_name`). States are logged as the name of their phase followed by a description
+    /// built from the `Display` implementations of states and errors, like the frames of
+    /// `run_sse` (e.g. `machine: reverting: halfway`), at the given `level`, except for reversion
+    /// progress, which is always logged at `Warn` (or `Error` for failed reversions).
+    #[cfg(feature = "log")]
+    pub fn log_progress(mut self, level: log::Level) -> Self
+    where
+        S: std::fmt::Display,
+        E: std::fmt::Display,
+    {
+        self.progress_logger = Some(Box::new(move |name, progress| {
+            let level = match progress {
+                #[cfg(feature = "revert")]
+                Progress::Revert(RevertProgress::Failure { .. }) => log::Level::Error,
+                #[cfg(feature = "revert")]
+                Progress::Revert(_) => log::Level::Warn,
+                #[cfg(not(feature = "revert"))]
+                Progress::Failed { .. } => log::Level::Error,
+                _ => level,
+            };
+
+            match sse::describe(progress) {
+                (phase, description) if description.is_empty() => {
+                    log::log!(level, "{}: {}", name, phase)
                 }
-                Progress::Revert(RevertProgress::Reverting { step, source }) => {
-                    match step.revert(context).await {
-                        Ok(None) => Some(Progress::Revert(RevertProgress::Reverted {
-                            source: source.clone(),
-                        })),
-                        Ok(Some(next)) => Some(Progress::Revert(RevertProgress::Reverting {
-                            step: next,
-                            source: source.clone(),
-                        })),
-                        Err(error) => Some(Progress::Revert(RevertProgress::Failure {
-                            source: source.clone(),
-                            error,
-                        })),
+                (phase, description) => log::log!(level, "{}: {}: {}", name, phase, description),
+            }
+        }));
+
+        self
+    }
+
+    /// Allow reversion to be aborted through the returned `AbortRevert` handle. Once aborted, the
+    /// `Streamline` stops before its next revert step and ends with a `RevertProgress::Failure`
+    /// containing the `RevertAborted` error.
+    #[cfg(feature = "revert")]
+    pub fn abortable(mut self) -> (Self, AbortRevert)
+    where
+        E: From<RevertAborted>,
+    {
+        let (abort_revert, abort) = abort::channel();
+
+        self.abort_handle = Some(abort);
+
+        (self, abort_revert)
+    }
+
+    /// Start reverting (or fail, without the `revert` feature) as soon as a forward transition
+    /// produces a state that isn't strictly greater than the state it came from, using the
+    /// `NotMonotonic` error. This catches accidental backward transitions in machines whose
+    /// states have a natural order (e.g. migration versions), and requires `S: PartialOrd` on
+    /// top of the usual `PartialEq` bound.
+    pub fn enforce_monotonic(mut self) -> Self
+    where
+        S: PartialOrd,
+        E: From<NotMonotonic>,
+    {
+        self.monotonic_check = Some(monotonic::check);
+
+        self
+    }
+
+    /// Start reverting (or fail, without the `revert` feature) when a state returns `Ok(None)`
+    /// from `next` without being declared final through `State::is_final`, using the
+    /// `UnexpectedTermination` error. This catches states that forgot a transition arm, but
+    /// requires every legitimate terminal state to implement `is_final`: final states end the
+    /// `Streamline` without running `next` at all, so any `Ok(None)` is unexpected.
+    pub fn strict_termination(mut self) -> Self
+    where
+        E: From<UnexpectedTermination>,
+    {
+        self.termination_error = Some(E::from);
+
+        self
+    }
+
+    /// Consult a `CircuitBreaker` shared between runs of this machine before running: if the
+    /// breaker is open, the `Streamline` ends immediately with the `CircuitOpen` error (in a
+    /// `RevertProgress::Failure`, or `Progress::Failed` without the `revert` feature) without
+    /// running any state. Otherwise, the way this run ends is recorded in the breaker: reverting
+    /// or failing counts as a failure, and completing resets the failure count. Runs that are
+    /// dropped before finishing aren't recorded.
+    pub fn with_breaker(mut self, breaker: CircuitBreaker) -> Self
+    where
+        E: From<CircuitOpen>,
+    {
+        self.breaker = Some(Breaker::new(breaker));
+
+        self
+    }
+
+    /// Revert states that declare an independent compensation (through
+    /// `State::independent_revert`) concurrently. The states to revert are recorded along the
+    /// forward path, and whenever reversion reaches an independent state, it is reverted at the
+    /// same time as every independent state that came right before it, as a single batch.
+    ///
+    /// This relaxes the usual guarantees of reversion for independent states only: their
+    /// `revert` calls may run in any order (and concurrently), they receive no context (since it
+    /// can't be borrowed mutably by several of them at once), their returned states are ignored
+    /// in favor of the recorded forward path, and they are not compensated through
+    /// `with_compensations`. The `RevertProgress::Reverting` items of a batch are all emitted
+    /// once the whole batch has been reverted, and the first error of a batch (in reversion
+    /// order) ends the reversion. Reversion of other states is unchanged.
+    #[cfg(feature = "revert")]
+    pub fn parallel_revert(mut self) -> Self {
+        self.parallel_revert = true;
+
+        self
+    }
+
+    /// Start reverting once a deadline has passed. The deadline is checked before each forward
+    /// transition, so in-flight transitions are never interrupted. Deadlines can be combined with
+    /// `run_preemptible` or `run_with_cancel`, in which case whichever is triggered first starts
+    /// the reversion process and any later cancellation is ignored.
+    pub fn deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+
+        self
+    }
+
+    /// Transform errors returned from `next` before they trigger a reversion, e.g. to enrich them
+    /// with details of the state that failed. The transformed error is the one stored as the
+    /// `source` of every subsequent `RevertProgress` item, including the terminal one.
+    #[cfg(feature = "revert")]
+    pub fn map_error_on_revert<F>(mut self, f: F) -> Self
+    where
+        F: Fn(E, &S) -> E + 'static,
+    {
+        self.error_mapper = Some(Box::new(f));
+
+        self
+    }
+
+    /// Pause forward progress while `predicate` holds for the context, for self-throttling
+    /// machines (e.g. backing off while a memory high-water mark recorded in the context is
+    /// exceeded). The predicate is checked before each forward transition and, while it holds,
+    /// re-checked every `interval` until it doesn't. Paused `Streamline`s still observe
+    /// cancellations and deadlines: a cancellation request ends the pause immediately and starts
+    /// reverting as usual, and so does reaching the deadline. `Streamline`s without a context
+    /// never pause, and reversion is never paused.
+    pub fn pause_when<F>(mut self, predicate: F, interval: Duration) -> Self
+    where
+        F: Fn(&C) -> bool + 'static,
+    {
+        self.pause = Some((Box::new(predicate), interval));
+
+        self
+    }
+
+    /// Only revert after a "point of no return": a state matching `predicate`. Every state is
+    /// checked against `predicate` just before it runs forward, and once one matches, errors
+    /// trigger a full reversion as usual. Errors from earlier states end the `Streamline` without
+    /// reverting anything, as if categorized as `ErrorCategory::Abort`. Tracking starts over with
+    /// each `Streamline`, so one started with `from_state` or `replay_from` only considers the
+    /// states it runs itself. Cancellations are unaffected.
+    #[cfg(feature = "revert")]
+    pub fn revert_only_after<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&S) -> bool + 'static,
+    {
+        self.point_of_no_return = Some((Box::new(predicate), false));
+
+        self
+    }
+
+    /// Skip states that have already run according to an `IdempotencyStore`. Before calling
+    /// `next` on a state with an `idempotency_key`, the store is checked: previously-run states
+    /// transition directly to their recorded next state without re-running their side effects.
+    pub fn idempotent<I>(mut self, store: I) -> Self
+    where
+        I: IdempotencyStore<S> + 'static,
+    {
+        self.idempotency_store = Some(Box::new(store));
+
+        self
+    }
+
+    /// Skip states that have already run according to the context's own `ContextIdempotency`
+    /// record, which travels with the context instead of living in a separate store. Before
+    /// calling `next` on a state with an `idempotency_key`, the context is checked, and
+    /// previously-run states transition directly to their recorded next state. Once `next`
+    /// succeeds, its result is recorded in the context. This can be combined with `idempotent`,
+    /// in which case the context is checked first.
+    pub fn idempotent_in_context(mut self) -> Self
+    where
+        C: ContextIdempotency<S>,
+    {
+        self.context_record = Some(ContextRecord::new());
+
+        self
+    }
+
+    /// Keep track of every state visited while moving forward, passing them all to
+    /// `State::next_with_history` rather than just the previous state. This keeps a clone of every
+    /// visited state for as long as the `Streamline` moves forward, so memory use grows with the
+    /// length of the path. The history is cleared as soon as reversion starts.
+    pub fn track_history(mut self) -> Self {
+        self.visited = Some(vec![]);
+
+        self
+    }
+
+    /// Durably record every state reached by a forward transition in a `WriteAheadLog` before
+    /// moving on, waiting for each append to finish. Once a state has been emitted, it has been
+    /// recorded, so a `Streamline` that crashes can be resumed from the last recorded state with
+    /// `from_state` (or rebuilt from its start state if nothing was recorded). The initial state
+    /// is not recorded. A failed append is handled like an error returned from the `next` that
+    /// led to the unrecorded state: by default, that transition is reverted.
+    pub fn with_wal<W>(mut self, wal: W) -> Self
+    where
+        W: WriteAheadLog<S, E> + 'static,
+    {
+        self.wal = Some(Box::new(wal));
+
+        self
+    }
+
+    /// Run the transitions of states that are batches (see `BatchState::batch`) item by item
+    /// through `BatchState::next_item` rather than through `next`, one at a time and in order
+    /// (unless allowed to run concurrently through `max_parallelism`). When a batch fails part of
+    /// the way through, the items that succeeded are compensated through
+    /// `BatchState::revert_items` right before the failed state is reverted (or before the
+    /// transition is retried), so that only those items are rolled back. Batches whose error
+    /// aborts the `Streamline` are not compensated.
+    #[cfg(feature = "revert")]
+    pub fn batched(mut self) -> Self
+    where
+        S: BatchState + 'static,
+        S::Handle: 'static,
+        E: 'static,
+    {
+        self.batch_runner = Some(batch::run_batch);
+
+        self
+    }
+
+    /// Run up to `n` items of a batch concurrently in a `batched` `Streamline`, rather than one at
+    /// a time. Items beyond the cap are queued until a slot frees up, which bounds resource usage
+    /// when the size of a batch is data-dependent, and the handles of a batch are collected in
+    /// completion order. On the first error, items that are still in flight are dropped. A
+    /// semaphore set through `with_semaphore` is only acquired once for the whole batch, so the
+    /// two limits compose as "at most `n` items within each of the permitted transitions".
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    #[cfg(feature = "revert")]
+    pub fn max_parallelism(mut self, n: usize) -> Self {
+        assert!(n > 0, "max_parallelism must be at least 1");
+
+        self.max_parallelism = n;
+
+        self
+    }
+
+    /// Compensate for states during reversion with the closures in a `CompensationRegistry`. An
+    /// explicit `State::revert` implementation takes precedence, with the registry only consulted
+    /// when `revert` returns `Ok(None)`.
+    #[cfg(feature = "revert")]
+    pub fn with_compensations(mut self, registry: CompensationRegistry<S, C, E>) -> Self {
+        self.compensations = Some(registry);
+
+        self
+    }
+
+    /// Limit concurrency across every `Streamline` sharing the same `Semaphore`. A permit is
+    /// acquired right before each call to `next` and released as soon as it returns, so the permit
+    /// is held only for the duration of a forward transition, never between transitions (or
+    /// during reversion).
+    #[cfg(feature = "tokio")]
+    pub fn with_semaphore(mut self, semaphore: Arc<Semaphore>) -> Self {
+        self.semaphore = Some(semaphore);
+
+        self
+    }
+
+    /// Run a cleanup callback with the way the `Streamline` ended and the (optional) context at
+    /// most once, when the `Streamline` finishes in any way: after completing, reverting, or
+    /// failing to revert. The callback runs right before the final state is emitted. If the
+    /// `Streamline` (or its Stream) is dropped before finishing, the callback is run on drop
+    /// instead, with `TerminalOutcome::Cancelled`. Only the last registered callback is kept.
+    pub fn finally<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(TerminalOutcome, Option<&mut C>) + 'static,
+    {
+        self.finalizer = Some(Box::new(f));
+
+        self
+    }
+
+    /// Call `f` with the state whose transition was running (forward through `State::next`, or
+    /// backward through `State::revert`) when a panic occurred, for diagnosing which state blew
+    /// up. The panic is caught with `catch_unwind`, and re-raised with `resume_unwind` right after
+    /// `f` returns, so the `Streamline` still panics: panics are never turned into reversions.
+    ///
+    /// `catch_unwind` requires the caught future to be `UnwindSafe`, which states and contexts
+    /// holding `&mut` references or interior mutability usually aren't. Since the panic is always
+    /// re-raised, nothing can observe state left broken by the panic (besides `f` itself and
+    /// `Drop` implementations, which are exposed to it anyway), so the transition is wrapped in
+    /// `AssertUnwindSafe` and no `UnwindSafe` bounds are required. `f` must not panic itself.
+    /// Only the last registered callback is kept.
+    pub fn on_panic<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&S) + 'static,
+    {
+        self.panic_hook = Some(Box::new(f));
+
+        self
+    }
+
+    /// Set the `RetryPolicy` used for errors that `State::categorize` reports as
+    /// `ErrorCategory::Retry`. Only the failed `next` call is retried: cancellation and deadlines
+    /// are not re-checked between attempts.
+    pub fn retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+
+        self
+    }
+
+    /// Set the `StepPolicy` for every forward transition, limiting each attempt at a transition
+    /// to the policy's timeout and retrying attempts that time out (or fail with errors
+    /// categorized as `ErrorCategory::Retry`) according to its `RetryPolicy`, which replaces any
+    /// policy set through `retry`. Attempts that time out fail with `StepTimedOut`, converted into
+    /// the `Streamline`'s error type, and once retries are exhausted that error is handled
+    /// according to `State::categorize` like any other.
+    ///
+    /// States that return a policy from `State::step_policy` use that policy instead, as a whole:
+    /// a per-state policy without a timeout disables the timeout for that state, even if this
+    /// policy has one. Per-state timeouts only take effect once a `Streamline`-wide policy has
+    /// been set (e.g. `StepPolicy::default()`), since that is what allows timeouts to be
+    /// converted into the `Streamline`'s error type.
+    pub fn step_policy(mut self, policy: StepPolicy) -> Self
+    where
+        E: From<StepTimedOut>,
+    {
+        self.retry_policy = policy.retries;
+        self.step_timeout = policy.timeout;
+        self.timeout_error = Some(E::from);
+
+        self
+    }
+
+    /// Sleep for a random duration between `min` and `max` before every transition (both forward
+    /// and backward), to simulate realistic transition timing in load tests without changing the
+    /// `State` implementation. Delays are random unless a seed is provided through `seeded`.
+    #[cfg(feature = "testing")]
+    pub fn with_jitter(mut self, min: Duration, max: Duration) -> Self {
+        self.jitter = Some((min, max));
+
+        self
+    }
+
+    /// Seed the random number generator used by the `Streamline` (e.g. for `with_jitter`, or
+    /// for retry jitter), so that every run with the same seed behaves the same way
+    #[cfg(feature = "testing")]
+    pub fn seeded(mut self, seed: u64) -> Self {
+        self.rng = Rng::seeded(seed);
+
+        self
+    }
+
+    /// Run a callback with the (optional) context whenever a transition produces a `Progress::Ok`
+    /// state equal to `target` (as determined by the state's `PartialEq` implementation). The
+    /// initial state is not produced by a transition, so it never triggers callbacks. Every
+    /// registered callback for a matching state is run, in the order they were registered.
+    pub fn on_state<F>(mut self, target: S, f: F) -> Self
+    where
+        F: Fn(&mut Option<C>) + 'static,
+    {
+        self.state_hooks.push((target, Box::new(f)));
+
+        self
+    }
+
+    /// Estimate the cost of the rest of the `Streamline` for scheduling purposes, by summing
+    /// `State::cost` along the expected forward path from the upcoming state (inclusive). The
+    /// expected path follows the first of each state's declared `State::transitions`, so this is a
+    /// best-effort estimate: the actual path is decided dynamically by `next`, and may differ.
+    /// Returns `None` if the `Streamline` is reverting or finished, or if the expected path
+    /// contains a cycle.
+    pub fn estimated_remaining_cost(&self) -> Option<u64> {
+        fraction::remaining_cost(self.upcoming_state()?)
+    }
+
+    /// The next state to run forward, if the `Streamline` is still moving forward
+    fn upcoming_state(&self) -> Option<S> {
+        self.current
+            .iter()
+            .chain(&self.pending)
+            .find_map(|progress| match progress {
+                Progress::Ok(state) => Some(state.clone()),
+                _ => None,
+            })
+    }
+
+    /// The states visited before the current one, as passed to `State::next_with_history`: the
+    /// full history if tracked through `track_history`, or the previous state otherwise
+    fn history(&self) -> &[S] {
+        match &self.visited {
+            Some(visited) => visited,
+            None => self.previous.as_slice(),
+        }
+    }
+
+    /// Every state reachable from the upcoming state (inclusive) through the declared
+    /// `State::transitions` that declares side effects through `State::has_side_effects`, in
+    /// breadth-first order. Like `estimated_remaining_cost`, this is static introspection over
+    /// declared transitions only. Returns an empty list if the `Streamline` is reverting or
+    /// finished.
+    pub fn side_effecting_states(&self) -> Vec<S> {
+        self.upcoming_state()
+            .map(fraction::reachable)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(State::has_side_effects)
+            .collect()
+    }
+
+    /// Allow cancellation through a `DebouncedCancel` handle that can withdraw its request. When
+    /// a cancellation is observed before a forward transition, the `Streamline` waits for `delay`
+    /// and checks again: it only starts reverting if the cancellation is still requested, and
+    /// otherwise carries on forward as if nothing happened. Every observed cancellation therefore
+    /// delays the next transition by `delay`, whether or not it is withdrawn. This replaces any
+    /// other cancellation source (e.g. from `run_preemptible`), though deadlines still apply.
+    pub fn cancel_debounced(mut self, delay: Duration) -> (Self, DebouncedCancel) {
+        let (cancel, cancellation) = cancel::debounced(delay);
+
+        self.cancellation_handle = Some(cancellation);
+
+        (self, cancel)
+    }
+
+    /// Allow cancellation through the returned `Cancel` handle, like `run_preemptible`, without
+    /// running the `Streamline` yet (e.g. to checkpoint it first). This replaces any other
+    /// cancellation source, though deadlines still apply.
+    pub fn preemptible(mut self) -> (Self, Cancel) {
+        let (cancel, cancellation) = cancel::channel();
+
+        self.cancellation_handle = Some(cancellation);
+
+        (self, cancel)
+    }
+
+    /// Remove the receiving end of the `Streamline`'s cancellation source (set up through e.g.
+    /// `preemptible` or `cancel_debounced`), if it has one, so that the rest of the `Streamline`
+    /// can be serialized. The `Streamline` is not preemptible while the receiver is detached:
+    /// cancellation requests are held back (not dropped) until it is attached again through
+    /// `attach_cancel`, though deadlines still apply.
+    ///
+    /// This only works on a `Streamline` that hasn't started: `run` (and every other way of
+    /// running it) consumes the `Streamline`, after which its cancellation source can no longer
+    /// be detached. To pause a running `Streamline` for serialization, run it with
+    /// `run_with_snapshots` and resume from a snapshot through `replay_from` instead.
+    pub fn detach_cancel(&mut self) -> Option<CancelReceiver> {
+        self.cancellation_handle.take().map(CancelReceiver)
+    }
+
+    /// Take ownership of the raw receiving end of a plain `Cancel` handle (as set up through
+    /// `preemptible`), for wiring it into a custom `select!` or combinator instead of going
+    /// through the `Streamline`. Returns `None` (leaving the cancellation source in place) for
+    /// any other kind of source, e.g. one that carries a reason or is debounced.
+    ///
+    /// Once taken, the receiver is no longer polled by the `Streamline`, which is not preemptible
+    /// anymore, so the two never compete for the cancellation signal. To keep cancelling the
+    /// `Streamline` itself, pass a signal derived from the receiver to `run_with_cancel`. Since
+    /// cancellations are no longer acknowledged, the `Future` returned by
+    /// `Cancel::cancel_and_wait` resolves right away.
+    pub fn take_cancellation_receiver(&mut self) -> Option<Receiver<()>> {
+        match self.cancellation_handle.take() {
+            Some(Cancellation::Receiver(receiver, _)) => Some(receiver),
+            cancellation_handle => {
+                self.cancellation_handle = cancellation_handle;
+
+                None
+            }
+        }
+    }
+
+    /// Attach a cancellation receiver removed through `detach_cancel` (e.g. from this
+    /// `Streamline` before it was serialized), making the `Streamline` preemptible again. This
+    /// replaces any other cancellation source. Like `detach_cancel`, this only works before the
+    /// `Streamline` is run.
+    pub fn attach_cancel(&mut self, receiver: CancelReceiver) {
+        self.cancellation_handle = Some(receiver.0);
+    }
+
+    /// Limit the number of items the `Streamline` can emit to `max`, protecting consumers with
+    /// bounded memory from runaway machines. Every item counts towards the limit, whether it was
+    /// emitted while moving forward or while reverting. Once `max` items have been emitted, an
+    /// unfinished `Streamline` stops cleanly without reverting: it emits a final
+    /// `Progress::Truncated` marker (which doesn't count towards the limit) and ends, and its
+    /// `finally` callback is passed `TerminalOutcome::Cancelled`.
+    pub fn max_emissions(mut self, max: usize) -> Self {
+        self.max_emissions = Some(max);
+
+        self
+    }
+
+    /// Generate a Stream of states, consuming the `Streamline`. The Stream is fused: once the
+    /// final state has been emitted, polling it again will always return `None`.
+    ///
+    /// The Stream only relies on the `Waker` of the task polling it, so it can be driven from
+    /// event loops that aren't built on futures: pin it (e.g. with `Box::pin`), then call
+    /// `Stream::poll_next` with a `Context` built around a custom `Waker`. `Poll::Pending` is
+    /// returned while a transition is waiting on something, and the `Waker` is woken once the
+    /// Stream should be polled again.
+    pub fn run(self) -> impl FusedStream<Item = Progress<S, E, C>> {
+        stream::unfold(self, Self::reduce).fuse()
+    }
+
+    /// Generate a Stream of states that runs ahead of its consumer by roughly `capacity` states,
+    /// consuming the `Streamline`. States are passed through a bounded channel: while the
+    /// consumer is busy, the `Streamline` keeps transitioning and buffers its states, and once the
+    /// buffer is full it stops transitioning until the consumer catches up (allowing for a single
+    /// extra state held by each side of the channel). Transitions are only
+    /// driven while the returned Stream is being polled, so no separate task is required.
+    pub fn buffered(self, capacity: usize) -> impl FusedStream<Item = Progress<S, E, C>> {
+        let (sender, receiver) = mpsc::channel(capacity);
+
+        let driver = self
+            .run()
+            .map(Ok)
+            .forward(sender)
+            .into_stream()
+            .filter_map(|_| future::ready(None));
+
+        stream::select(receiver, driver)
+    }
+
+    /// Return a Stream of states and a cancellation handle
+    pub fn run_preemptible(self) -> (impl FusedStream<Item = Progress<S, E, C>>, Cancel) {
+        let (streamline, cancel) = self.preemptible();
+
+        (streamline.run(), cancel)
+    }
+
+    /// Return a Stream of states and a cancellation handle that carries a typed reason (see
+    /// `Cancel::cancel_with_reason`), so consumers can match on cancellation causes. The reason
+    /// is emitted through `RevertProgress::Cancelled` (or `Progress::Cancelled`, without the
+    /// `revert` feature) right after `Progress::CancellationRequested`, and the reversion that
+    /// follows has no `source`, like any other cancellation. The items of the Stream carry the
+    /// reason type `R` as the last generic parameter of `Progress`, unlike those of every other
+    /// Stream returned from a `Streamline`, which use the default `()`.
+    pub fn run_preemptible_with_reason<R>(
+        mut self,
+    ) -> (impl FusedStream<Item = Progress<S, E, C, R>>, Cancel<R>)
+    where
+        R: 'static,
+    {
+        let (cancel, cancellation, slot) = cancel::channel_with_reason();
+
+        self.cancellation_handle = Some(cancellation);
+
+        let stream = self
+            .run()
+            .filter_map(move |progress| future::ready(progress.with_reason(|| slot.take())));
+
+        (stream, cancel)
+    }
+
+    /// Add an extra cancellation signal on top of any existing cancellation source (e.g. from
+    /// `preemptible` or `cancel_debounced`), so that either of them cancels the `Streamline`
+    pub(crate) fn or_cancel_on<F>(mut self, signal: F) -> Self
+    where
+        F: Future<Output = ()> + 'static,
+    {
+        let signal = Box::pin(signal);
+
+        self.cancellation_handle = Some(match self.cancellation_handle.take() {
+            Some(cancellation) => cancellation.or_signal(signal),
+            None => Cancellation::Signal(signal),
+        });
+
+        self
+    }
+
+    /// Return a Stream of states that begins reverting once an external cancellation signal
+    /// resolves (e.g. a shutdown notification from an existing channel). Like `run_preemptible`,
+    /// the signal is checked before each forward transition, so in-flight transitions are never
+    /// interrupted.
+    pub fn run_with_cancel<F>(mut self, signal: F) -> impl FusedStream<Item = Progress<S, E, C>>
+    where
+        F: Future<Output = ()> + 'static,
+    {
+        self.cancellation_handle = Some(Cancellation::Signal(Box::pin(signal)));
+
+        self.run()
+    }
+
+    /// Generate a Stream of states that starts reverting once a deadline has passed, consuming the
+    /// `Streamline`. Useful for stopping several `Streamline`s at the same moment. See `deadline`
+    /// for combining deadlines with other forms of cancellation.
+    pub fn run_with_deadline(
+        self,
+        deadline: Instant,
+    ) -> impl FusedStream<Item = Progress<S, E, C>> {
+        self.deadline(deadline).run()
+    }
+
+    /// Generate a Stream of only the reversion phase of the `Streamline`, consuming it. The
+    /// machine is run as usual, but nothing is yielded until reversion begins, starting from the
+    /// first `RevertProgress::Reverting` item (or the `RevertProgress::Cancelled` item before it,
+    /// for cancellations that carry a reason). The Stream ends without yielding anything if the
+    /// `Streamline` completes without reverting.
+    #[cfg(feature = "revert")]
+    pub fn revert_stream(self) -> impl FusedStream<Item = RevertProgress<S, E, C>> {
+        self.run().filter_map(|progress| async move {
+            match progress {
+                Progress::Revert(revert_progress) => Some(revert_progress),
+                _ => None,
+            }
+        })
+    }
+
+    /// Generate a Stream of states that skips any state produced within `min_interval` of the
+    /// previously emitted one, consuming the `Streamline`. The first and final states are always
+    /// emitted. Only emission is throttled: transitions still run as fast as they can, and any
+    /// skipped states are discarded rather than delayed.
+    pub fn throttle(self, min_interval: Duration) -> impl FusedStream<Item = Progress<S, E, C>> {
+        stream::unfold(
+            (self, None),
+            move |(mut state_machine, last_emitted): (Self, Option<Instant>)| async move {
+                loop {
+                    let progress = state_machine.transition().await?;
+                    let now = Instant::now();
+                    let is_final = state_machine.current.is_none();
+                    let throttled =
+                        last_emitted.is_some_and(|last_emitted| now - last_emitted < min_interval);
+
+                    if is_final || !throttled {
+                        return Some((progress, (state_machine, Some(now))));
                     }
                 }
-                _ => None,
-            };
+            },
+        )
+        .fuse()
+    }
+
+    /// Generate a Stream of states tagged with a sequence number, consuming the `Streamline`.
+    /// Sequence numbers start at `0` and increase by one for every emitted state, across both
+    /// forward progress and reversion, so that states can be put back in order after being sent
+    /// through channels or merged with other Streams.
+    pub fn run_sequenced(self) -> impl FusedStream<Item = (u64, Progress<S, E, C>)> {
+        stream::unfold((self, 0), |(mut state_machine, sequence)| async move {
+            let progress = state_machine.transition().await?;
+
+            Some(((sequence, progress), (state_machine, sequence + 1)))
+        })
+        .fuse()
+    }
+
+    /// Generate a Stream of states, consuming the `Streamline`, along with a `TraceHandle` that
+    /// retains clones of the most recent `window` emitted items for inspection (e.g. for
+    /// diagnosing a stuck or failed machine). Older items are dropped as new ones are emitted,
+    /// so memory use is bounded by the window regardless of how long the `Streamline` runs, at
+    /// the cost of losing the start of long traces. Collect the Stream itself for a complete
+    /// trace.
+    pub fn run_traced_bounded(
+        self,
+        window: usize,
+    ) -> (
+        impl FusedStream<Item = Progress<S, E, C>>,
+        TraceHandle<S, E, C>,
+    )
+    where
+        E: Clone,
+    {
+        let trace = TraceHandle::new(window);
+        let recorder = trace.clone();
+
+        let stream = self
+            .run()
+            .inspect(move |progress| recorder.record(progress));
+
+        (stream, trace)
+    }
+
+    /// Return a Stream of states and a `ControlHandle` for manually intervening in the
+    /// `Streamline` while it runs (e.g. forcing the next state through
+    /// `ControlHandle::force_next`)
+    pub fn run_controlled(
+        mut self,
+    ) -> (impl FusedStream<Item = Progress<S, E, C>>, ControlHandle<S>) {
+        let control = ControlHandle::new();
 
-            if let Some(next_state) = next_state {
-                let current = std::mem::replace(&mut state_machine.current, next_state);
+        self.control = Some(control.clone());
 
-                Some((current, Some(state_machine)))
+        (self.run(), control)
+    }
+
+    /// Generate a Stream of states paired with the wall-clock time at which each was emitted,
+    /// formatted as an RFC 3339 timestamp in UTC with millisecond precision (e.g.
+    /// `2020-03-14T15:09:26.535Z`, see `rfc3339`), consuming the `Streamline`. Timestamps come
+    /// from the system clock, so they can go backwards if the clock is adjusted. Only available
+    /// with the `timestamps` feature, which formats them through the `time` crate.
+    #[cfg(feature = "timestamps")]
+    pub fn run_timestamped(self) -> impl FusedStream<Item = (String, Progress<S, E, C>)> {
+        self.run()
+            .map(|progress| (timestamp::rfc3339(SystemTime::now()), progress))
+    }
+
+    /// Generate a Stream of states paired with the metadata attached through `with_metadata`,
+    /// consuming the `Streamline`. The metadata is shared between every item rather than cloned,
+    /// and is empty if none was attached.
+    pub fn run_with_metadata(
+        self,
+    ) -> impl FusedStream<Item = (Progress<S, E, C>, Arc<HashMap<String, String>>)> {
+        let metadata = self.metadata.clone();
+
+        self.run().map(move |progress| (progress, metadata.clone()))
+    }
+
+    /// Generate a Stream of states paired with the fraction (from `0.0` to `1.0`) of the
+    /// `Streamline` completed as of each state, consuming the `Streamline`. While moving forward,
+    /// the fraction is the share of the total `State::cost` already spent, with the remaining cost
+    /// estimated along the declared `State::transitions` (like `estimated_remaining_cost`).
+    /// While reverting, the fraction counts the states reverted so far, out of the reversion
+    /// path declared through `State::revert_transitions` from the first reverted state, and
+    /// reaches `1.0` once reverted. The fraction is `None` (indeterminate) when the relevant
+    /// transitions aren't declared (or contain a cycle), and after a failure. Markers and other
+    /// intermediate items carry the latest fraction.
+    pub fn run_with_progress(self) -> impl FusedStream<Item = (Progress<S, E, C>, Option<f64>)> {
+        stream::unfold(
+            (self, Fraction::default()),
+            |(mut state_machine, mut fraction)| async move {
+                let progress = state_machine.transition().await?;
+                let completed = fraction.update(&progress, state_machine.current.is_none());
+
+                Some(((progress, completed), (state_machine, fraction)))
+            },
+        )
+        .fuse()
+    }
+
+    /// Gracefully shut down a `Streamline` that hasn't started running: cancel it (as if through
+    /// a `Cancel` handle) and drive the resulting reversion to completion, discarding every
+    /// emitted state. To shut a `Streamline` down part-way through a run, run it through
+    /// `run_with_shutdown` and call `RunningStreamline::shutdown` instead. Unlike dropping a
+    /// `Streamline` part-way through, this gives async `revert`s and compensations a chance to
+    /// run, since `Drop` can only run synchronous cleanup (like the `finally` callback).
+    pub async fn shutdown(mut self) {
+        self.cancellation_handle = Some(Cancellation::Signal(Box::pin(future::ready(()))));
+
+        while self.transition().await.is_some() {}
+    }
+
+    /// Drive the `Streamline` to completion, returning only the final state. Intermediate states
+    /// are discarded as soon as they are produced, so this avoids collecting the full history of
+    /// a long-running `Streamline` when only the outcome matters.
+    pub async fn last(mut self) -> Option<Progress<S, E, C>> {
+        let mut last = None;
+
+        while let Some(progress) = self.transition().await {
+            last = Some(progress);
+        }
+
+        last
+    }
+
+    /// Walk the forward path from the upcoming state against a mock context, returning every
+    /// state visited (starting with the upcoming state), or the first error returned from
+    /// `next`. This is a planning and testing aid: nothing is ever reverted, and cancellation,
+    /// deadlines, retries, delays, hooks, and the `finally` callback are all skipped. Note that
+    /// `next` still runs for real, so any side effects it has outside of the mock context will
+    /// happen. Returns an empty list if the `Streamline` is reverting or has already finished.
+    pub async fn trace_forward(mut self, mock_context: C) -> Result<Vec<S>, E> {
+        self.finalizer = None;
+
+        let mut context = mock_context;
+
+        let mut state = match self.upcoming_state() {
+            Some(state) => state,
+            None => return Ok(vec![]),
+        };
+
+        let mut visited: Vec<S> = vec![];
+
+        loop {
+            let next = if state.is_final() {
+                None
             } else {
-                Some((state_machine.current, None))
+                let history = [self.history(), &visited].concat();
+
+                state
+                    .next_with_history(&history, Some(&mut context))
+                    .await?
+            };
+
+            visited.push(state);
+
+            match next {
+                Some(next) => state = next,
+                None => return Ok(visited),
             }
-        } else {
-            None
+        }
+    }
+
+    /// Drive the `Streamline` to completion, returning how it ended: the last state reached on
+    /// completion, or the errors involved in reverting. Returns `None` if the `Streamline` has
+    /// already finished, or if it was truncated through `max_emissions`.
+    #[cfg(feature = "revert")]
+    pub async fn outcome(mut self) -> Option<Outcome<S, E>> {
+        self.drive().await
+    }
+
+    /// Drive the `Streamline` to completion, returning a `RunReport` summarizing the run: the
+    /// states visited, how it ended, how long it took and how many transitions it made (forward and
+    /// backward). This is
+    /// a shortcut for folding the Stream from `run` by hand. Since the report includes the full
+    /// path, memory use grows with the length of the path.
+    #[cfg(feature = "revert")]
+    pub async fn run_report(mut self) -> RunReport<S, E> {
+        let started = Instant::now();
+        let mut path = vec![];
+        let mut outcome = None;
+        let mut forward: usize = 0;
+        let mut reversions = 0;
+
+        while let Some(progress) = self.transition().await {
+            outcome = match progress {
+                Progress::Ok(state) => {
+                    path.push(state.clone());
+                    forward += 1;
+
+                    Some(Outcome::Completed(state))
+                }
+                Progress::Revert(RevertProgress::Reverting { step, .. }) => {
+                    path.push(step);
+                    reversions += 1;
+
+                    outcome
+                }
+                Progress::Revert(RevertProgress::Reverted { source, .. }) => {
+                    Some(Outcome::Reverted { source })
+                }
+                Progress::Revert(RevertProgress::Failure { source, error }) => {
+                    Some(Outcome::Failed { source, error })
+                }
+                Progress::Truncated => None,
+                _ => outcome,
+            };
+        }
+
+        RunReport {
+            // the initial state is emitted without a transition into it
+            transitions: forward.saturating_sub(1),
+            reversions,
+            path,
+            outcome,
+            duration: started.elapsed(),
+        }
+    }
+
+    /// Drive the `Streamline` to completion, then start the next stage of a multi-stage workflow
+    /// from `next`, with a context upgraded from this `Streamline`'s context through `f` (e.g. to
+    /// a richer context type needed by later stages). The old context is consumed by `f`, after
+    /// the `finally` callback (if any) has run. The next stage has no context if this one didn't
+    /// (including contexts attached through `share_context`, which are not moved). If this
+    /// `Streamline` doesn't complete, its `Outcome` is returned instead and no next stage is
+    /// built. Intermediate states are discarded, as with `outcome`.
+    #[cfg(feature = "revert")]
+    pub async fn map_context<F, C2, S2>(
+        mut self,
+        next: S2,
+        f: F,
+    ) -> Result<Streamline<C2, E, S2>, Outcome<S, E>>
+    where
+        F: FnOnce(C) -> C2,
+        S2: State<Context = C2, Error = E>,
+    {
+        match self.drive().await {
+            Some(Outcome::Completed(_)) | None => {
+                let streamline = Streamline::build(next);
+
+                match self.context.take() {
+                    Some(context) => Ok(streamline.context(f(context))),
+                    None => Ok(streamline),
+                }
+            }
+            Some(outcome) => Err(outcome),
+        }
+    }
+
+    /// Drive the `Streamline` to completion in place (see `outcome`)
+    #[cfg(feature = "revert")]
+    async fn drive(&mut self) -> Option<Outcome<S, E>> {
+        let mut outcome = None;
+
+        while let Some(progress) = self.transition().await {
+            outcome = match progress {
+                Progress::Ok(state) => Some(Outcome::Completed(state)),
+                Progress::Revert(RevertProgress::Reverted { source, .. }) => {
+                    Some(Outcome::Reverted { source })
+                }
+                Progress::Revert(RevertProgress::Failure { source, error }) => {
+                    Some(Outcome::Failed { source, error })
+                }
+                Progress::Truncated => None,
+                _ => outcome,
+            };
+        }
+
+        outcome
+    }
+
+    /// Drive the `Streamline` to completion from within a parent state's `next`, for building
+    /// hierarchical machines. Returns the last state reached if the nested `Streamline`
+    /// completes, which the parent can then map to its own next state. If the nested
+    /// `Streamline` reverts instead, its `Outcome` is converted into the parent's error type
+    /// (through a `From<Outcome<S, E>>` implementation) so that it can be returned with `?` to
+    /// revert the parent in turn. The nested `Streamline` has its own context, so any context
+    /// shared with the parent must be passed in explicitly (e.g. through an `Arc`).
+    #[cfg(feature = "revert")]
+    pub async fn run_nested<P>(self) -> Result<Option<S>, P>
+    where
+        P: From<Outcome<S, E>>,
+    {
+        match self.outcome().await {
+            Some(Outcome::Completed(state)) => Ok(Some(state)),
+            Some(outcome) => Err(P::from(outcome)),
+            None => Ok(None),
+        }
+    }
+
+    /// Drive the `Streamline` to completion on the current thread, returning every state. This is
+    /// meant for purely computational machines whose `next` and `revert` never need to wait on
+    /// I/O (e.g. in CLIs or tests without an async runtime). Machines doing real async I/O should
+    /// use `run` on an appropriate executor instead, since this blocks the current thread and
+    /// does not provide the reactors that I/O or timer futures depend on.
+    #[cfg(feature = "blocking")]
+    pub fn collect_blocking(self) -> Vec<Progress<S, E, C>> {
+        futures::executor::block_on(self.run().collect())
+    }
+
+    async fn reduce(mut state_machine: Self) -> Option<(Progress<S, E, C>, Self)> {
+        let progress = state_machine.transition().await?;
+
+        Some((progress, state_machine))
+    }
+
+    /// Run the transition out of the current state, returning the current state once the
+    /// transition has completed (or `None` if the final state has already been emitted)
+    async fn transition(&mut self) -> Option<Progress<S, E, C>> {
+        // move a shared context into the Streamline for the duration of the transition
+        self.lock_shared_context().await;
+
+        self.check_version();
+
+        let progress = self.advance().await;
+
+        if let Some(version_check) = &mut self.version_check {
+            version_check.record(self.context.as_ref());
+        }
+
+        self.unlock_shared_context();
+
+        let progress = progress?;
+
+        self.emissions += 1;
+
+        #[cfg(feature = "log")]
+        if let Some(log_progress) = &self.progress_logger {
+            log_progress(self.name.as_deref().unwrap_or("streamline"), &progress);
+        }
+
+        Some(progress)
+    }
+
+    /// Lock a shared context (if any) and move it into the `Streamline`, unless it is already
+    /// locked (e.g. by a transition that was interrupted)
+    async fn lock_shared_context(&mut self) {
+        if self.shared_guard.is_some() {
+            return;
+        }
+
+        if let Some(shared_context) = &self.shared_context {
+            let mut guard = shared_context.lock_owned().await;
+
+            self.context = guard.take();
+            self.shared_guard = Some(guard);
+        }
+    }
+
+    /// Move a locked shared context (if any) back into its `SharedContext`, and unlock it
+    fn unlock_shared_context(&mut self) {
+        if let Some(mut guard) = self.shared_guard.take() {
+            *guard = self.context.take();
+        }
+    }
+
+    /// Check whether a `Versioned` context was modified since the end of the last transition (if
+    /// enabled), announcing the modification with a warning before the upcoming state or
+    /// reverting from it
+    fn check_version(&mut self) {
+        let version_check = match &self.version_check {
+            Some(version_check) => version_check,
+            None => return,
+        };
+
+        let state = match &self.current {
+            Some(Progress::Ok(state)) => state.clone(),
+            _ => return,
+        };
+
+        self.current = match version_check.check(self.context.as_ref()) {
+            Some((_, Some(error))) => Some(Self::stop(&state, Some(error))),
+            Some((modified, None)) => {
+                self.pending.push_front(Progress::Ok(state.clone()));
+
+                Some(Progress::Warning {
+                    state,
+                    message: modified.to_string(),
+                })
+            }
+            None => return,
+        };
+    }
+
+    /// Advance the `Streamline` by a single state (see `transition`)
+    async fn advance(&mut self) -> Option<Progress<S, E, C>> {
+        if self.max_emissions.is_some_and(|max| self.emissions >= max)
+            && (self.current.is_some() || self.idle.is_some())
+        {
+            self.max_emissions = None;
+            self.current = None;
+            self.idle = None;
+            self.pending.clear();
+            self.finalize(TerminalOutcome::Cancelled);
+
+            return Some(Progress::Truncated);
+        }
+
+        if self.start_marker && self.current.is_some() {
+            self.start_marker = false;
+
+            return Some(Progress::Started(Instant::now()));
+        }
+
+        if let Some(error) = self.breaker.as_mut().and_then(Breaker::rejected) {
+            self.pending.clear();
+            self.current = Some(Self::abort(error));
+        }
+
+        // resume the transition of an idle or waiting state without emitting it again
+        if let Some(state) = self.idle.take() {
+            self.resuming = true;
+            self.step_guarded(&Progress::Ok(state)).await;
+        }
+
+        let current = self.current.take()?;
+
+        match &current {
+            Progress::Done => {}
+            Progress::Idle { state } | Progress::Waiting { state, .. } => {
+                self.idle = Some(state.clone())
+            }
+            current => self.step_guarded(current).await,
+        }
+
+        Some(current)
+    }
+
+    /// Run `step`, passing the state being transitioned to the `on_panic` hook (if any) before
+    /// re-raising a panic
+    async fn step_guarded(&mut self, current: &Progress<S, E, C>) {
+        if self.panic_hook.is_none() {
+            return self.step(current).await;
+        }
+
+        if let Err(payload) = AssertUnwindSafe(self.step(current)).catch_unwind().await {
+            let state = match current {
+                Progress::Ok(state) => Some(state),
+                #[cfg(feature = "revert")]
+                Progress::Revert(RevertProgress::Reverting { step, .. }) => Some(step),
+                _ => None,
+            };
+
+            if let (Some(hook), Some(state)) = (&self.panic_hook, state) {
+                hook(state);
+            }
+
+            panic::resume_unwind(payload);
+        }
+    }
+
+    /// Compute the state that comes after `current`, setting it as the new current state
+    async fn step(&mut self, current: &Progress<S, E, C>) {
+        #[cfg(feature = "opentelemetry")]
+        let span = {
+            let name = self.name.as_deref().unwrap_or("streamline");
+
+            self.telemetry
+                .get_or_insert_with(|| Telemetry::new(name))
+                .start(current)
+        };
+
+        let next_state = match current {
+            Progress::Ok(inner) => self.step_forward(inner).await,
+            Progress::SubStep { .. }
+            | Progress::Warning { .. }
+            | Progress::CancellationRequested { .. } => self.pending.pop_front(),
+            #[cfg(feature = "revert")]
+            Progress::Revert(RevertProgress::Cancelled { .. }) => self.pending.pop_front(),
+            #[cfg(not(feature = "revert"))]
+            Progress::Cancelled { .. } => self.pending.pop_front(),
+            #[cfg(feature = "revert")]
+            Progress::Revert(RevertProgress::Reverting { step, source }) => {
+                self.step_back(step, source).await
+            }
+            _ => None,
+        };
+
+        #[cfg(feature = "opentelemetry")]
+        if let (Some(telemetry), Some(span)) = (&mut self.telemetry, span) {
+            telemetry.end(span, current, &next_state);
+        }
+
+        #[cfg(feature = "opentelemetry")]
+        if next_state.is_none() {
+            self.telemetry = None;
+        }
+
+        if next_state.is_none() {
+            if let Some(breaker) = &self.breaker {
+                breaker.record(matches!(current, Progress::Ok(_)));
+            }
+
+            self.finalize(TerminalOutcome::of(current));
+        }
+
+        // the history is only needed while moving forward
+        if let (Some(visited), Some(Phase::Reverting | Phase::Reverted | Phase::Failed)) = (
+            &mut self.visited,
+            next_state.as_ref().and_then(Progress::phase),
+        ) {
+            *visited = vec![];
+        }
+
+        let next_state = match next_state {
+            None if self.completion_marker => Some(Progress::Done),
+            next_state => next_state,
+        };
+
+        if let Some(Progress::Ok(next)) = &next_state {
+            for (target, hook) in &self.state_hooks {
+                if target == next {
+                    hook(&mut self.context);
+                }
+            }
+        }
+
+        self.current = next_state;
+    }
+
+    /// Sleep for a random duration before a transition, if configured through `with_jitter`
+    #[cfg(feature = "testing")]
+    async fn sleep_jitter(&mut self) {
+        if let Some((min, max)) = self.jitter {
+            self.unlock_shared_context();
+            futures_timer::Delay::new(self.rng.duration_between(min, max)).await;
+            self.lock_shared_context().await;
+        }
+    }
+
+    /// Wait for as long as the `pause_when` predicate holds, returning `true` if the pause was
+    /// interrupted by a cancellation request
+    async fn wait_while_paused(&mut self) -> bool {
+        loop {
+            let interval = match (&self.pause, &self.context) {
+                (Some((predicate, interval)), Some(context)) if predicate(context) => *interval,
+                _ => return false,
+            };
+
+            if self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+            {
+                return false;
+            }
+
+            if self.sleep_until(Instant::now() + interval).await {
+                return true;
+            }
+        }
+    }
+
+    /// Sleep until a state's `State::delay_until`, or until the deadline if it comes first,
+    /// returning `true` if the sleep was interrupted by a cancellation request
+    async fn sleep_until(&mut self, until: Instant) -> bool {
+        let until = match self.deadline {
+            Some(deadline) => until.min(deadline),
+            None => until,
+        };
+
+        let delay = futures_timer::Delay::new(until.saturating_duration_since(Instant::now()));
+
+        self.unlock_shared_context();
+
+        let interrupted = match &mut self.cancellation_handle {
+            Some(cancellation) => {
+                let requested = cancellation.requested();
+
+                futures::pin_mut!(requested);
+
+                matches!(
+                    future::select(delay, requested).await,
+                    future::Either::Right(_)
+                )
+            }
+            None => {
+                delay.await;
+
+                false
+            }
+        };
+
+        self.lock_shared_context().await;
+
+        interrupted
+    }
+
+    /// Run the cleanup callback registered through `finally`, if it hasn't been run yet
+    fn finalize(&mut self, outcome: TerminalOutcome) {
+        if let Some(finalizer) = self.finalizer.take() {
+            match (&self.shared_context, self.context.as_mut()) {
+                (Some(shared_context), None) => match shared_context.try_lock() {
+                    Some(mut shared_context) => finalizer(outcome, shared_context.as_mut()),
+                    None => finalizer(outcome, None),
+                },
+                (_, context) => finalizer(outcome, context),
+            }
+        }
+    }
+
+    /// Transition forward from a `Progress::Ok` state, or start reverting on cancellation or error
+    async fn step_forward(&mut self, inner: &S) -> Option<Progress<S, E, C>> {
+        if inner.is_final() {
+            return None;
+        }
+
+        // announce states that are about to wait before running the wait itself
+        let resuming = std::mem::take(&mut self.resuming);
+
+        if self.idle_marker && !resuming {
+            if let Some(until) = inner.delay_until() {
+                if until > Instant::now() {
+                    return Some(Progress::Idle {
+                        state: inner.clone(),
+                    });
+                }
+            }
+        }
+
+        // announce states that are about to wait on an external resource
+        if !resuming {
+            if let Some(reason) = inner.waiting_on() {
+                return Some(Progress::Waiting {
+                    state: inner.clone(),
+                    reason,
+                });
+            }
+        }
+
+        #[cfg(feature = "revert")]
+        if self.parallel_revert {
+            self.history.push(inner.clone());
+        }
+
+        #[cfg(feature = "revert")]
+        if let Some((predicate, crossed)) = &mut self.point_of_no_return {
+            *crossed = *crossed || predicate(inner);
+        }
+
+        // hold cancellation requests back until a cancellable state is reached
+        let deferred = if inner.is_cancellable() {
+            None
+        } else {
+            self.cancellation_handle.take()
+        };
+
+        #[cfg(feature = "testing")]
+        self.sleep_jitter().await;
+
+        let interrupted = self.wait_while_paused().await
+            || match inner.delay_until() {
+                Some(until) => self.sleep_until(until).await,
+                None => false,
+            };
+
+        // Before moving to the next state, check that the current
+        // streamline hasn't been cancelled externally or run out of time
+        let expired = self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline);
+
+        let cancelled = !expired
+            && (interrupted
+                || match &mut self.cancellation_handle {
+                    Some(cancellation) => cancellation.is_cancelled().await,
+                    None => false,
+                });
+
+        if deferred.is_some() {
+            self.cancellation_handle = deferred;
+        }
+
+        if expired || cancelled {
+            let reasoned = self
+                .cancellation_handle
+                .take()
+                .is_some_and(Cancellation::acknowledge);
+
+            // announce observed cancellations before acting on them
+            if cancelled {
+                if reasoned {
+                    self.pending.push_back(Self::cancelled(inner));
+                }
+
+                self.pending.push_back(Self::stop(inner, None));
+
+                return Some(Progress::CancellationRequested {
+                    step: inner.clone(),
+                });
+            }
+
+            return Some(Self::stop(inner, None));
+        }
+
+        let (retry_policy, timeout) = match inner.step_policy() {
+            Some(StepPolicy { timeout, retries }) => (retries, timeout),
+            None => (self.retry_policy.clone(), self.step_timeout),
+        };
+
+        let timeout = timeout.zip(self.timeout_error);
+
+        let mut attempt = 0;
+
+        let next = loop {
+            // states forced through a ControlHandle replace the transition entirely
+            if let Some(forced) = self.control.as_ref().and_then(ControlHandle::take_forced) {
+                break Ok(Some(forced));
+            }
+
+            let (next, timed_out) = match timeout {
+                Some((timeout, timeout_error)) => {
+                    let next = Box::pin(self.run_next(inner));
+
+                    match future::select(next, futures_timer::Delay::new(timeout)).await {
+                        Either::Left((next, _)) => (next, false),
+                        Either::Right(_) => (Err(timeout_error(StepTimedOut)), true),
+                    }
+                }
+                None => (self.run_next(inner).await, false),
+            };
+
+            match next {
+                Err(source)
+                    if (timed_out || inner.categorize(&source) == ErrorCategory::Retry)
+                        && attempt < retry_policy.max_retries() =>
+                {
+                    // roll back the items of a failed batch before trying it again
+                    #[cfg(feature = "revert")]
+                    if let Some(compensate) = self.partial_batch.take() {
+                        if let Err(error) = compensate(self.context.as_mut()).await {
+                            break Err(error);
+                        }
+                    }
+
+                    self.unlock_shared_context();
+                    futures_timer::Delay::new(retry_policy.delay(attempt, &mut self.rng)).await;
+                    self.lock_shared_context().await;
+                    attempt += 1;
+                }
+                next => break next,
+            }
+        };
+
+        let next = match (next, self.monotonic_check) {
+            (Ok(Some(next)), Some(check)) => check(inner, &next).map(|()| Some(next)),
+            (next, _) => next,
+        };
+
+        let next = match (next, self.termination_error) {
+            (Ok(None), Some(termination_error)) => Err(termination_error(UnexpectedTermination)),
+            (next, _) => next,
+        };
+
+        let next = match (next, &self.wal) {
+            (Ok(Some(next)), Some(wal)) => wal.append(&next).await.map(|()| Some(next)),
+            (next, _) => next,
+        };
+
+        match next {
+            Ok(next) => {
+                // queue up any reported sub-steps and warnings to be emitted
+                // before the next state itself
+                let sub_steps = inner
+                    .sub_steps()
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, label)| Progress::SubStep {
+                        state: inner.clone(),
+                        index,
+                        label,
+                    });
+
+                self.pending.extend(sub_steps);
+
+                if let Some(next) = next {
+                    self.previous = Some(inner.clone());
+
+                    if let Some(visited) = &mut self.visited {
+                        visited.push(inner.clone());
+                    }
+
+                    let warnings =
+                        next.take_warnings()
+                            .into_iter()
+                            .map(|message| Progress::Warning {
+                                state: next.clone(),
+                                message,
+                            });
+
+                    self.pending.extend(warnings);
+                    self.pending.push_back(Progress::Ok(next));
+                }
+
+                self.pending.pop_front()
+            }
+            Err(source) => {
+                let category = inner.categorize(&source);
+
+                #[cfg(feature = "revert")]
+                let source = match &self.error_mapper {
+                    Some(map_error) => map_error(source, inner),
+                    None => source,
+                };
+
+                #[cfg(feature = "revert")]
+                let category = match &self.point_of_no_return {
+                    Some((_, false)) => ErrorCategory::Abort,
+                    _ => category,
+                };
+
+                match category {
+                    ErrorCategory::Abort => {
+                        #[cfg(feature = "revert")]
+                        {
+                            self.partial_batch = None;
+                        }
+
+                        Some(Self::abort(source))
+                    }
+                    _ => Some(Self::stop(inner, Some(source))),
+                }
+            }
+        }
+    }
+
+    /// Stop moving forward from a state after an error (or cancellation, if there is no error),
+    /// starting the reversion process
+    #[cfg(feature = "revert")]
+    fn stop(inner: &S, source: Option<E>) -> Progress<S, E, C> {
+        Progress::Revert(RevertProgress::Reverting {
+            step: inner.clone(),
+            source: source.map(|source| Arc::new(inner.summarize_error(source))),
+        })
+    }
+
+    /// Stop moving forward from a state after an error (or cancellation, if there is no error),
+    /// ending the `Streamline`
+    #[cfg(not(feature = "revert"))]
+    fn stop(_inner: &S, source: Option<E>) -> Progress<S, E, C> {
+        Progress::Failed { source }
+    }
+
+    /// Report a cancellation that carried a reason, which is only attached to the reported item
+    /// by `run_preemptible_with_reason`
+    #[cfg(feature = "revert")]
+    fn cancelled(inner: &S) -> Progress<S, E, C> {
+        Progress::Revert(RevertProgress::Cancelled {
+            reason: (),
+            step: inner.clone(),
+        })
+    }
+
+    /// Report a cancellation that carried a reason, which is only attached to the reported item
+    /// by `run_preemptible_with_reason`
+    #[cfg(not(feature = "revert"))]
+    fn cancelled(inner: &S) -> Progress<S, E, C> {
+        Progress::Cancelled {
+            reason: (),
+            step: inner.clone(),
+        }
+    }
+
+    /// End the `Streamline` after an error categorized as `ErrorCategory::Abort` (or a rejection
+    /// by a `CircuitBreaker`), without reverting any previous states
+    #[cfg(feature = "revert")]
+    fn abort(error: E) -> Progress<S, E, C> {
+        Progress::Revert(RevertProgress::Failure {
+            source: None,
+            error,
+        })
+    }
+
+    /// End the `Streamline` after an error categorized as `ErrorCategory::Abort` (or a rejection
+    /// by a `CircuitBreaker`), which is no different from any other error without the `revert`
+    /// feature
+    #[cfg(not(feature = "revert"))]
+    fn abort(error: E) -> Progress<S, E, C> {
+        Progress::Failed {
+            source: Some(error),
+        }
+    }
+
+    /// Run a state's `next` method, skipping states that have already run according to the
+    /// `IdempotencyStore` (if one exists)
+    async fn run_next(&mut self, inner: &S) -> Result<Option<S>, E> {
+        let idempotency_key = match (&self.idempotency_store, &self.context_record) {
+            (None, None) => None,
+            _ => inner.idempotency_key(),
+        };
+
+        let previous_run = match (&self.context_record, &self.context, &idempotency_key) {
+            (Some(record), Some(context), Some(key)) => (record.has_run)(context, key),
+            _ => None,
+        };
+
+        let previous_run = match (previous_run, &self.idempotency_store, &idempotency_key) {
+            (None, Some(store), Some(key)) => store.has_run(key).await,
+            (previous_run, ..) => previous_run,
+        };
+
+        if let Some(next) = previous_run {
+            return Ok(next);
+        }
+
+        #[cfg(feature = "tokio")]
+        let permit = match self.semaphore.clone() {
+            Some(semaphore) => {
+                self.unlock_shared_context();
+
+                let permit = semaphore.acquire_owned().await;
+
+                self.lock_shared_context().await;
+
+                Some(permit)
+            }
+            None => None,
+        };
+
+        let next = match self.run_batch(inner).await {
+            Some(next) => next,
+            None => {
+                // borrow the history and the context separately
+                let history = match &self.visited {
+                    Some(visited) => visited,
+                    None => self.previous.as_slice(),
+                };
+
+                inner
+                    .next_with_history(history, self.context.as_mut())
+                    .await
+            }
+        };
+
+        #[cfg(feature = "tokio")]
+        drop(permit);
+
+        if let (Some(record), Some(context), Some(key), Ok(next)) = (
+            &self.context_record,
+            &mut self.context,
+            &idempotency_key,
+            &next,
+        ) {
+            (record.mark_run)(context, key.clone(), next.clone());
+        }
+
+        if let (Some(store), Some(key), Ok(next)) =
+            (&self.idempotency_store, idempotency_key, &next)
+        {
+            store.mark_run(key, next.clone()).await;
+        }
+
+        next
+    }
+
+    /// Run a state's transition through `BatchState::next_item` in a `batched` `Streamline`,
+    /// keeping the compensation of a failed batch's partial items for later. Returns `None` if
+    /// the state is not a batch.
+    #[cfg(feature = "revert")]
+    async fn run_batch(&mut self, inner: &S) -> Option<Result<Option<S>, E>> {
+        let batch =
+            (self.batch_runner?)(inner, self.max_parallelism, self.context.as_ref()).await?;
+
+        Some(batch.map_err(|(error, compensate)| {
+            self.partial_batch = Some(compensate);
+
+            error
+        }))
+    }
+
+    /// Run a state's transition as a batch, which is never the case without the `revert` feature
+    #[cfg(not(feature = "revert"))]
+    async fn run_batch(&mut self, _inner: &S) -> Option<Result<Option<S>, E>> {
+        None
+    }
+
+    /// Transition backward from a `RevertProgress::Reverting` state
+    #[cfg(feature = "revert")]
+    async fn step_back(&mut self, step: &S, source: &Option<Arc<E>>) -> Option<Progress<S, E, C>> {
+        // states that were reverted as part of a parallel batch only need to be reported
+        if self.batched > 0 {
+            self.batched -= 1;
+
+            return self.pending.pop_front();
+        }
+
+        #[cfg(feature = "testing")]
+        self.sleep_jitter().await;
+
+        if let Some(error) = self.abort_handle.as_mut().and_then(Abort::aborted) {
+            return Some(Progress::Revert(RevertProgress::Failure {
+                source: source.clone(),
+                error,
+            }));
+        }
+
+        // roll back the items of a failed batch before reverting the batch's state itself
+        let items_compensated = match self.partial_batch.take() {
+            Some(compensate) => match compensate(self.context.as_mut()).await {
+                Ok(()) => true,
+                Err(error) => {
+                    return Some(Progress::Revert(RevertProgress::Failure {
+                        source: source.clone(),
+                        error,
+                    }))
+                }
+            },
+            None => false,
+        };
+
+        if self.parallel_revert {
+            if self.history.last() == Some(step) {
+                self.history.pop();
+            }
+
+            if step.independent_revert() {
+                return self.revert_batch(step, items_compensated, source).await;
+            }
+        }
+
+        let mut compensated = items_compensated;
+
+        let reverted = match step.revert(self.context.as_mut()).await {
+            Ok(None) => match self
+                .compensations
+                .as_ref()
+                .and_then(|registry| registry.get(step))
+            {
+                Some(compensate) => {
+                    compensated = true;
+
+                    compensate(step, self.context.as_mut()).await
+                }
+                None => Ok(None),
+            },
+            Ok(Some(next)) => {
+                compensated = true;
+
+                Ok(Some(next))
+            }
+            reverted => reverted,
+        };
+
+        if compensated {
+            self.compensated.push(step.clone());
+        }
+
+        let next_state = match reverted {
+            Ok(None) => RevertProgress::Reverted {
+                source: source.clone(),
+                compensated_states: std::mem::take(&mut self.compensated),
+            },
+            Ok(Some(next)) => RevertProgress::Reverting {
+                step: next,
+                source: source.clone(),
+            },
+            Err(error) => RevertProgress::Failure {
+                source: source.clone(),
+                error,
+            },
+        };
+
+        Some(Progress::Revert(next_state))
+    }
+
+    /// Revert a state along with every independent state that came right before it on the
+    /// recorded forward path, concurrently (see `parallel_revert`)
+    #[cfg(feature = "revert")]
+    async fn revert_batch(
+        &mut self,
+        step: &S,
+        items_compensated: bool,
+        source: &Option<Arc<E>>,
+    ) -> Option<Progress<S, E, C>> {
+        let mut batch = vec![step.clone()];
+
+        while self
+            .history
+            .last()
+            .is_some_and(|previous| previous.independent_revert())
+        {
+            batch.extend(self.history.pop());
+        }
+
+        let reverted = future::join_all(batch.iter().map(|state| state.revert(None))).await;
+
+        let mut compensated = vec![];
+
+        for (index, (state, reverted)) in batch.iter().zip(reverted).enumerate() {
+            match reverted {
+                Ok(Some(_)) => compensated.push(state.clone()),
+                // the items of a failed batch were already rolled back for the first state
+                Ok(None) if index == 0 && items_compensated => compensated.push(state.clone()),
+                Ok(None) => {}
+                Err(error) => {
+                    return Some(Progress::Revert(RevertProgress::Failure {
+                        source: source.clone(),
+                        error,
+                    }))
+                }
+            }
+        }
+
+        self.compensated.extend(compensated);
+
+        // report the rest of the batch before moving on
+        for state in batch.into_iter().skip(1) {
+            self.batched += 1;
+            self.pending
+                .push_back(Progress::Revert(RevertProgress::Reverting {
+                    step: state,
+                    source: source.clone(),
+                }));
+        }
+
+        let next_state = match self.history.last() {
+            Some(previous) => RevertProgress::Reverting {
+                step: previous.clone(),
+                source: source.clone(),
+            },
+            None => RevertProgress::Reverted {
+                source: source.clone(),
+                compensated_states: std::mem::take(&mut self.compensated),
+            },
+        };
+
+        self.pending.push_back(Progress::Revert(next_state));
+        self.pending.pop_front()
+    }
+}
+
+impl<C, E, S> Drop for Streamline<C, E, S>
+where
+    S: State<Context = C, Error = E>,
+{
+    fn drop(&mut self) {
+        self.finalize(TerminalOutcome::Cancelled);
+
+        // return a shared context taken by an interrupted transition
+        self.unlock_shared_context();
+    }
+}
+
+impl<C, E, S> Streamline<C, E, S>
+where
+    S: State<Context = C, Error = E>,
+    C: Clone,
+{
+    /// Compute the state that the current state would transition to, without advancing the
+    /// `Streamline`. `next` is run against a clone of the context, so any changes it makes to the
+    /// context are discarded. Use with caution: `next` is not guaranteed to be pure, so any other
+    /// side effects it has (e.g. calls to external services) will still happen. Returns `Ok(None)`
+    /// if the `Streamline` is reverting or has already finished.
+    pub async fn peek_next(&self) -> Result<Option<S>, E> {
+        match &self.current {
+            Some(Progress::Ok(current)) => {
+                let mut context = self.context.clone();
+
+                current
+                    .next_with_history(self.history(), context.as_mut())
+                    .await
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Generate a Stream of states paired with a snapshot of the context taken right after each
+    /// state's transition has run, consuming the `Streamline`. Unlike `run`, this requires a
+    /// `Clone`-able context, and clones it once for every emitted state, so prefer cheaply-cloned
+    /// contexts (or contexts behind an `Arc`) for long-running machines. The snapshot is `None` if
+    /// no context was provided.
+    pub fn run_with_snapshots(self) -> impl FusedStream<Item = (Progress<S, E, C>, Option<C>)> {
+        stream::unfold(self, |mut state_machine| async move {
+            let progress = state_machine.transition().await?;
+            let snapshot = state_machine.context.clone();
+
+            Some(((progress, snapshot), state_machine))
+        })
+        .fuse()
+    }
+}
+
+impl<C, E, S> Streamline<C, E, S>
+where
+    S: State<Context = C, Error = E>,
+    C: Clone + PartialEq,
+{
+    /// Generate a Stream of states paired with whether the context changed while producing them,
+    /// consuming the `Streamline`, to pinpoint which transition mutated the context. Like
+    /// `run_with_snapshots`, this clones the context once per emitted state to compare it with
+    /// the context after the transition, so it requires a `Clone + PartialEq` context. Contexts
+    /// attached through `share_context` are not observed, and never report changes.
+    pub fn run_with_context_diff(self) -> impl FusedStream<Item = (Progress<S, E, C>, bool)> {
+        stream::unfold(self, |mut state_machine| async move {
+            let before = state_machine.context.clone();
+            let progress = state_machine.transition().await?;
+            let changed = state_machine.context != before;
+
+            Some(((progress, changed), state_machine))
+        })
+        .fuse()
+    }
+}
+
+impl<C, E, S> Streamline<C, E, S>
+where
+    S: State<Context = C, Error = E> + Debug,
+    C: Default,
+{
+    /// Catch `Streamline`s that would do nothing in debug builds, by running the upcoming
+    /// state's `next` once against a default context and panicking if it's immediately terminal
+    /// (i.e. it returns `Ok(None)`). Errors are ignored, and this is a no-op in release builds.
+    ///
+    /// This is opt-in because `next` really runs, so any side effects it has outside of the
+    /// (throwaway) context will happen, and will happen again once the `Streamline` runs for
+    /// real. Only use this with states whose `next` is safe to run twice.
+    pub async fn debug_assert_progresses(self) -> Self {
+        #[cfg(debug_assertions)]
+        if let Some(state) = self.upcoming_state() {
+            let mut context = C::default();
+            let next = state.next_with_history(&[], Some(&mut context)).await;
+
+            assert!(
+                !matches!(next, Ok(None)),
+                "Streamline starting from {:?} is immediately terminal",
+                state
+            );
+        }
+
+        self
+    }
+}
+
+impl<E, S, T> Streamline<Versioned<T>, E, S>
+where
+    S: State<Context = Versioned<T>, Error = E>,
+{
+    /// Detect `Versioned` contexts modified outside of the `Streamline` (e.g. through
+    /// `SharedContext::with`) between two transitions, emitting a `Progress::Warning` for the
+    /// upcoming state right before it when they are. The version of the context is recorded at
+    /// the end of every transition, and compared with the version at the start of the next one:
+    /// mutable access from the `Streamline`'s own states, hooks, and callbacks during a
+    /// transition is expected, and never reported. Only forward transitions are checked.
+    pub fn warn_on_context_modification(mut self) -> Self {
+        self.version_check = Some(VersionCheck::new(Versioned::version, None));
+
+        self
+    }
+
+    /// Detect `Versioned` contexts modified outside of the `Streamline` between two transitions,
+    /// like `warn_on_context_modification`, but start reverting from the upcoming state (or stop,
+    /// without the `revert` feature) with a `ContextModified` error instead of warning. The
+    /// upcoming state is reverted without being emitted, since its forward transition never
+    /// runs.
+    pub fn revert_on_context_modification(mut self) -> Self
+    where
+        E: From<ContextModified>,
+    {
+        self.version_check = Some(VersionCheck::new(Versioned::version, Some(E::from)));
+
+        self
+    }
+}
+
+impl<E, S, T> Streamline<LogCapture<T>, E, S>
+where
+    S: State<Context = LogCapture<T>, Error = E>,
+{
+    /// Generate a single ordered timeline of states and the lines that states logged to their
+    /// `LogCapture` context, consuming the `Streamline`. States are wrapped in `Either::Left`,
+    /// and log lines in `Either::Right`.
+    ///
+    /// A state is only emitted once the transition out of it has completed, and the lines logged
+    /// during that transition are emitted right after it, in the order they were logged. Lines
+    /// logged by a state's `next` (or `revert`) therefore always come after that state, and
+    /// before the state it transitions into. Lines logged from outside of the `Streamline` (e.g.
+    /// through `SharedContext::with`) are emitted after the next state instead.
+    pub fn run_with_logs(
+        self,
+    ) -> impl FusedStream<Item = Either<Progress<S, E, LogCapture<T>>, LogLine>> {
+        stream::unfold(
+            (self, VecDeque::new()),
+            |(mut state_machine, mut lines)| async move {
+                if let Some(line) = lines.pop_front() {
+                    return Some((Either::Right(line), (state_machine, lines)));
+                }
+
+                let progress = state_machine.transition().await?;
+
+                lines.extend(state_machine.take_log_lines());
+
+                Some((Either::Left(progress), (state_machine, lines)))
+            },
+        )
+        .fuse()
+    }
+
+    /// Take the lines captured by the context since they were last taken
+    fn take_log_lines(&mut self) -> Vec<LogLine> {
+        match (&mut self.context, &self.shared_context) {
+            (Some(context), _) => context.take_lines(),
+            (None, Some(shared_context)) => shared_context
+                .try_lock()
+                .and_then(|mut context| context.as_mut().map(LogCapture::take_lines))
+                .unwrap_or_default(),
+            (None, None) => vec![],
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+mod checkpoint {
+    use super::*;
+    use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+
+    /// The serializable parts of a `Streamline`
+    #[derive(Serialize)]
+    #[serde(bound(serialize = "S: Serialize, E: Serialize, C: Serialize"))]
+    struct CheckpointRef<'a, C, E, S>
+    where
+        S: State<Context = C, Error = E>,
+    {
+        context: &'a Option<C>,
+        current: &'a Option<Progress<S, E, C>>,
+        pending: &'a VecDeque<Progress<S, E, C>>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(bound(deserialize = "S: DeserializeOwned, E: DeserializeOwned, C: DeserializeOwned"))]
+    struct Checkpoint<C, E, S>
+    where
+        S: State<Context = C, Error = E>,
+    {
+        context: Option<C>,
+        current: Option<Progress<S, E, C>>,
+        pending: VecDeque<Progress<S, E, C>>,
+    }
+
+    /// Checkpoints the initial configuration of a `Streamline` that has not started running: its
+    /// starting progress and context. Running a `Streamline` consumes it, so the bookkeeping of a
+    /// run in progress (retry counters, visited states, history, compensations, and so on) is
+    /// never part of a checkpoint. To checkpoint part-way through a run, rebuild the `Streamline`
+    /// from the state reached with `replay_from` and from the context at that point (e.g. as
+    /// captured by `run_with_snapshots`), then serialize that. Cancellation handles, deadlines,
+    /// and callback-based configuration can not be serialized, and are skipped.
+    impl<C, E, S> Serialize for Streamline<C, E, S>
+    where
+        S: State<Context = C, Error = E> + Serialize,
+        E: Serialize,
+        C: Serialize,
+    {
+        fn serialize<T>(&self, serializer: T) -> Result<T::Ok, T::Error>
+        where
+            T: Serializer,
+        {
+            CheckpointRef {
+                context: &self.context,
+                current: &self.current,
+                pending: &self.pending,
+            }
+            .serialize(serializer)
+        }
+    }
+
+    /// Restores a checkpointed `Streamline`, which starts from the progress and context it was
+    /// serialized with. Any configuration skipped during serialization must be re-applied, and
+    /// preemptibility must be re-enabled (e.g. through `run_preemptible`, or `attach_cancel` with
+    /// a receiver detached before serializing) after deserializing.
+    impl<'de, C, E, S> Deserialize<'de> for Streamline<C, E, S>
+    where
+        S: State<Context = C, Error = E> + DeserializeOwned,
+        E: DeserializeOwned,
+        C: DeserializeOwned,
+    {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let checkpoint = Checkpoint::deserialize(deserializer)?;
+
+            let mut streamline = Self::from_progress(checkpoint.current);
+
+            streamline.context = checkpoint.context;
+            streamline.pending = checkpoint.pending;
+
+            Ok(streamline)
         }
     }
 }
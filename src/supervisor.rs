@@ -0,0 +1,227 @@
+#[cfg(feature = "revert")]
+use crate::{
+    outcome::{Outcome, TerminalOutcome},
+    progress::RevertProgress,
+};
+use crate::{
+    progress::{Phase, Progress},
+    state::State,
+    streamline::Streamline,
+};
+use futures::{
+    channel::oneshot::{self, Sender},
+    future::{self, FutureExt},
+    stream::{self, FusedStream, LocalBoxStream, StreamExt},
+};
+use std::collections::HashMap;
+
+/// Identifier for a `Streamline` run by a `Supervisor`
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct MachineId(usize);
+
+/// A `Supervisor` drives many `Streamline`s concurrently, merging their progress into a single
+/// Stream and allowing them to be cancelled collectively
+pub struct Supervisor<C, E, S>
+where
+    S: State<Context = C, Error = E>,
+{
+    machines: Vec<Streamline<C, E, S>>,
+}
+
+impl<C, E, S> Default for Supervisor<C, E, S>
+where
+    S: State<Context = C, Error = E>,
+{
+    fn default() -> Self {
+        Self { machines: vec![] }
+    }
+}
+
+impl<C, E, S> Supervisor<C, E, S>
+where
+    S: State<Context = C, Error = E> + 'static,
+    C: 'static,
+    E: 'static,
+{
+    /// Create an empty `Supervisor`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a `Streamline` to the `Supervisor`, returning the `MachineId` that its progress will
+    /// be tagged with
+    pub fn add(&mut self, streamline: Streamline<C, E, S>) -> MachineId {
+        self.machines.push(streamline);
+
+        MachineId(self.machines.len() - 1)
+    }
+
+    /// Run every supervised `Streamline` concurrently, returning a single Stream of progress
+    /// tagged with the `MachineId` of the `Streamline` that produced it, and a `CancelAll` handle
+    /// that cancels every `Streamline` at once. There are no ordering guarantees between
+    /// different `Streamline`s: their progress is interleaved in whatever order it is produced,
+    /// though the progress of any single `Streamline` is always emitted in order.
+    ///
+    /// `CancelAll` is combined with any cancellation source that a `Streamline` already had
+    /// (e.g. from `Streamline::preemptible` or `Streamline::cancel_debounced`) rather than
+    /// replacing it, so either of them cancels that `Streamline`.
+    pub fn run_all(
+        self,
+    ) -> (
+        impl FusedStream<Item = (MachineId, Progress<S, E, C>)>,
+        CancelAll,
+    ) {
+        let (sender, receiver) = oneshot::channel::<()>();
+        let signal = receiver.shared();
+
+        let streams = self
+            .machines
+            .into_iter()
+            .enumerate()
+            .map(|(index, streamline)| {
+                let signal = signal.clone();
+
+                streamline
+                    .or_cancel_on(async move {
+                        // dropping the CancelAll handle should not cancel anything
+                        if signal.await.is_err() {
+                            future::pending::<()>().await;
+                        }
+                    })
+                    .run()
+                    .map(move |progress| (MachineId(index), progress))
+                    .boxed_local()
+            })
+            .collect::<Vec<LocalBoxStream<_>>>();
+
+        (stream::select_all(streams), CancelAll { sender })
+    }
+
+    /// Run every supervised `Streamline` concurrently (like `run_all`), emitting only the changes
+    /// in each `Streamline`'s `Phase` rather than every item of progress, to cut down on the
+    /// volume of updates for large fleets (e.g. when feeding a dashboard). The first phase of
+    /// each `Streamline` is always emitted. Every transition within a phase is suppressed, as
+    /// are the `Progress::Started` and `Progress::Done` markers, which don't belong to any phase.
+    pub fn run_phase_changes(self) -> impl FusedStream<Item = (MachineId, Phase)> {
+        let (stream, _) = self.run_all();
+        let mut phases = HashMap::new();
+
+        stream.filter_map(move |(id, progress)| {
+            let change = progress
+                .phase()
+                .filter(|phase| phases.insert(id, *phase) != Some(*phase))
+                .map(|phase| (id, phase));
+
+            future::ready(change)
+        })
+    }
+
+    /// Run every supervised `Streamline` concurrently (like `run_all`), cancelling all of them as
+    /// soon as any one of them starts reverting or aborts (or fails, without the `revert`
+    /// feature), so that the group succeeds or rolls back as a whole. Cancellation follows the
+    /// usual rules: siblings start reverting before their next forward transition, and siblings
+    /// that have already finished are left as they are. Every `Streamline`'s reversion progress
+    /// is still reported through the returned Stream.
+    pub fn run_all_or_cancel(self) -> impl FusedStream<Item = (MachineId, Progress<S, E, C>)> {
+        let (stream, cancel_all) = self.run_all();
+        let mut cancel_all = Some(cancel_all);
+
+        stream.inspect(move |(_, progress)| {
+            #[cfg(feature = "revert")]
+            let stopped = matches!(
+                progress,
+                Progress::Revert(RevertProgress::Reverting { .. } | RevertProgress::Failure { .. })
+            );
+
+            #[cfg(not(feature = "revert"))]
+            let stopped = matches!(progress, Progress::Failed { .. });
+
+            if stopped {
+                if let Some(cancel_all) = cancel_all.take() {
+                    cancel_all.cancel_all().ok();
+                }
+            }
+        })
+    }
+}
+
+/// Cancellation handle returned by `Supervisor::run_all` that triggers the revert process of
+/// every supervised `Streamline`
+pub struct CancelAll {
+    sender: Sender<()>,
+}
+
+impl CancelAll {
+    /// Cancel every `Streamline` associated with the parent `Supervisor`
+    #[allow(clippy::result_unit_err)]
+    pub fn cancel_all(self) -> Result<(), ()> {
+        self.sender.send(())
+    }
+}
+
+/// Run several `Streamline`s concurrently (e.g. redundant implementations of the same
+/// workflow), returning the final state of the first one to complete successfully. Every other
+/// `Streamline` is then cancelled, and reverted (following the usual cancellation rules)
+/// before returning, so that their async reverts get to run. If none of them complete, every
+/// `Outcome` is returned instead, in the order the `Streamline`s were given. `Streamline`s
+/// truncated through `Streamline::max_emissions` never win, and have no `Outcome` to return.
+///
+/// If several `Streamline`s complete at around the same time, whichever completion is observed
+/// first wins, with no further guarantees. The `Streamline`s should not share a context (or any
+/// other resources that racing could corrupt): build each of them with its own.
+#[cfg(feature = "revert")]
+pub async fn race<C, E, S>(machines: Vec<Streamline<C, E, S>>) -> Result<S, Vec<Outcome<S, E>>>
+where
+    S: State<Context = C, Error = E> + 'static,
+    C: 'static,
+    E: 'static,
+{
+    let mut supervisor = Supervisor::new();
+
+    for machine in machines {
+        supervisor.add(machine.emit_completion_marker());
+    }
+
+    let count = supervisor.machines.len();
+    let (mut stream, cancel_all) = supervisor.run_all();
+    let mut cancel_all = Some(cancel_all);
+    let mut outcomes: Vec<Option<Outcome<S, E>>> = (0..count).map(|_| None).collect();
+    let mut terminals = vec![None; count];
+    let mut winner = None;
+
+    while let Some((MachineId(index), progress)) = stream.next().await {
+        let outcome = &mut outcomes[index];
+
+        if !matches!(progress, Progress::Done) {
+            terminals[index] = Some(TerminalOutcome::of(&progress));
+        }
+
+        match progress {
+            Progress::Ok(state) => *outcome = Some(Outcome::Completed(state)),
+            Progress::Revert(RevertProgress::Reverted { source, .. }) => {
+                *outcome = Some(Outcome::Reverted { source })
+            }
+            Progress::Revert(RevertProgress::Failure { source, error }) => {
+                *outcome = Some(Outcome::Failed { source, error })
+            }
+            Progress::Truncated => *outcome = None,
+            Progress::Done if winner.is_none() => {
+                if let (Some(TerminalOutcome::Completed), Some(Outcome::Completed(state))) =
+                    (terminals[index], outcome)
+                {
+                    winner = Some(state.clone());
+
+                    if let Some(cancel_all) = cancel_all.take() {
+                        cancel_all.cancel_all().ok();
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    match winner {
+        Some(state) => Ok(state),
+        None => Err(outcomes.into_iter().flatten().collect()),
+    }
+}
@@ -0,0 +1,104 @@
+#[cfg(feature = "revert")]
+use crate::progress::RevertProgress;
+use crate::{progress::Progress, state::State};
+use opentelemetry::{
+    global::{self, BoxedSpan, BoxedTracer},
+    trace::{Span, Status, TraceContextExt, Tracer},
+    Context,
+};
+
+/// OpenTelemetry spans for the transitions of a single `Streamline`. Every transition is recorded
+/// as a child of a span covering the whole `Streamline`, and reversion spans are linked to the
+/// span of the transition whose error triggered the reversion.
+pub(crate) struct Telemetry {
+    name: String,
+    tracer: BoxedTracer,
+    machine: Context,
+    #[cfg(feature = "revert")]
+    error_span: Option<opentelemetry::trace::SpanContext>,
+}
+
+impl Telemetry {
+    /// Start the span covering the whole `Streamline`
+    pub(crate) fn new(name: &str) -> Self {
+        let tracer = global::tracer("streamline");
+        let machine = Context::current_with_span(tracer.start(name.to_string()));
+
+        Self {
+            name: name.to_string(),
+            tracer,
+            machine,
+            #[cfg(feature = "revert")]
+            error_span: None,
+        }
+    }
+
+    /// Start a span for the transition out of `current`, or `None` if `current` is only a
+    /// reporting item without a transition of its own
+    pub(crate) fn start<S, E, C>(&self, current: &Progress<S, E, C>) -> Option<BoxedSpan>
+    where
+        S: State<Context = C, Error = E>,
+    {
+        let (direction, links) = match current {
+            Progress::Ok(_) => ("next", vec![]),
+            #[cfg(feature = "revert")]
+            Progress::Revert(RevertProgress::Reverting { .. }) => (
+                "revert",
+                self.error_span
+                    .iter()
+                    .cloned()
+                    .map(opentelemetry::trace::Link::with_context)
+                    .collect(),
+            ),
+            _ => return None,
+        };
+
+        let span = self
+            .tracer
+            .span_builder(format!("{}::{}", self.name, direction))
+            .with_links(links)
+            .start_with_context(&self.tracer, &self.machine);
+
+        Some(span)
+    }
+
+    /// End a transition's span, recording any error produced by the transition
+    pub(crate) fn end<S, E, C>(
+        &mut self,
+        mut span: BoxedSpan,
+        current: &Progress<S, E, C>,
+        next: &Option<Progress<S, E, C>>,
+    ) where
+        S: State<Context = C, Error = E>,
+    {
+        match (current, next) {
+            #[cfg(feature = "revert")]
+            (
+                Progress::Ok(_),
+                Some(Progress::Revert(RevertProgress::Reverting {
+                    source: Some(_), ..
+                })),
+            ) => {
+                span.set_status(Status::error("transition failed"));
+                self.error_span = Some(span.span_context().clone());
+            }
+            #[cfg(feature = "revert")]
+            (_, Some(Progress::Revert(RevertProgress::Failure { .. }))) => {
+                span.set_status(Status::error("reversion failed"));
+            }
+            #[cfg(not(feature = "revert"))]
+            (_, Some(Progress::Failed { source: Some(_) })) => {
+                span.set_status(Status::error("transition failed"));
+            }
+            _ => {}
+        }
+
+        span.end();
+    }
+}
+
+impl Drop for Telemetry {
+    fn drop(&mut self) {
+        self.machine.span().end();
+    }
+}
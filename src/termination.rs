@@ -0,0 +1,15 @@
+use std::{error::Error, fmt};
+
+/// The error used to start reverting a `Streamline` built with `Streamline::strict_termination`
+/// when a state that isn't declared final through `State::is_final` returns `Ok(None)` from
+/// `next`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UnexpectedTermination;
+
+impl fmt::Display for UnexpectedTermination {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("unexpected termination from a state that isn't final")
+    }
+}
+
+impl Error for UnexpectedTermination {}
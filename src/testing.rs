@@ -0,0 +1,76 @@
+//! Helpers for asserting how a `Streamline` behaves in tests
+use crate::{progress::Progress, state::State, streamline::Streamline};
+use futures::{pin_mut, task::noop_waker_ref, Stream, StreamExt};
+use std::{
+    fmt::Debug,
+    task::{Context, Poll},
+};
+
+/// Drive a Stream of states to completion, panicking if any `Progress::Ok` state is equal to
+/// `forbidden`
+pub async fn assert_no_transition_to<St, S, E, C>(stream: St, forbidden: S)
+where
+    St: Stream<Item = Progress<S, E, C>>,
+    S: State<Context = C, Error = E> + Debug,
+{
+    pin_mut!(stream);
+
+    while let Some(progress) = stream.next().await {
+        if let Progress::Ok(state) = progress {
+            assert!(
+                state != forbidden,
+                "Streamline transitioned to forbidden state {:?}",
+                forbidden
+            );
+        }
+    }
+}
+
+/// Drive a Stream of states until a `Progress::Ok` state is equal to `target`, panicking if the
+/// Stream ends without ever reaching it
+pub async fn assert_reaches<St, S, E, C>(stream: St, target: S)
+where
+    St: Stream<Item = Progress<S, E, C>>,
+    S: State<Context = C, Error = E> + Debug,
+{
+    pin_mut!(stream);
+
+    while let Some(progress) = stream.next().await {
+        if matches!(&progress, Progress::Ok(state) if *state == target) {
+            return;
+        }
+    }
+
+    panic!("Streamline never reached target state {:?}", target);
+}
+
+/// Run a `Streamline` twice, each time from a fresh instance returned by `build`, panicking if
+/// the two runs emit different states. This catches accidental nondeterminism, such as
+/// `HashMap` iteration order leaking into transitions. Any side effects of the `Streamline`
+/// happen twice, so `build` should give each run its own context.
+pub async fn assert_deterministic<F, C, E, S>(build: F)
+where
+    F: Fn() -> Streamline<C, E, S>,
+    S: State<Context = C, Error = E>,
+    Progress<S, E, C>: Debug + PartialEq,
+{
+    let first: Vec<_> = build().run().collect().await;
+    let second: Vec<_> = build().run().collect().await;
+
+    assert_eq!(first, second, "Streamline is not deterministic");
+}
+
+/// Poll a `Streamline`'s Stream of states exactly once with a no-op waker, without a runtime.
+/// This returns `Poll::Pending` if the first transition is waiting on anything (e.g. a timer),
+/// and otherwise the first state, so it's best suited to `Streamline`s whose states complete
+/// without waiting.
+pub fn poll_once<C, E, S>(streamline: Streamline<C, E, S>) -> Poll<Option<Progress<S, E, C>>>
+where
+    S: State<Context = C, Error = E>,
+{
+    let stream = streamline.run();
+
+    pin_mut!(stream);
+
+    stream.poll_next_unpin(&mut Context::from_waker(noop_waker_ref()))
+}
@@ -0,0 +1,23 @@
+//! RFC 3339 formatting for `Streamline::run_timestamped`, through the `time` crate behind the
+//! `timestamps` feature so that the core crate stays dependency-light.
+use std::time::SystemTime;
+use time::OffsetDateTime;
+
+/// Format a wall-clock time as an RFC 3339 timestamp in UTC with millisecond precision (e.g.
+/// `2020-03-14T15:09:26.535Z`), as used by `Streamline::run_timestamped`. Sub-millisecond
+/// precision is truncated towards the past, and times before the Unix epoch are formatted like
+/// any other (e.g. `1969-12-31T23:59:59.999Z`). Only available with the `timestamps` feature.
+pub fn rfc3339(time: SystemTime) -> String {
+    let time = OffsetDateTime::from(time);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        time.year(),
+        u8::from(time.month()),
+        time.day(),
+        time.hour(),
+        time.minute(),
+        time.second(),
+        time.millisecond()
+    )
+}
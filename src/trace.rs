@@ -0,0 +1,61 @@
+use crate::{progress::Progress, state::State};
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+/// Handle returned by `Streamline::run_traced_bounded` for reading the most recent progress of
+/// the traced `Streamline`, while it runs or after it has finished
+pub struct TraceHandle<S, E, C>
+where
+    S: State<Context = C, Error = E>,
+{
+    window: usize,
+    history: Arc<Mutex<VecDeque<Progress<S, E, C>>>>,
+}
+
+impl<S, E, C> Clone for TraceHandle<S, E, C>
+where
+    S: State<Context = C, Error = E>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            window: self.window,
+            history: self.history.clone(),
+        }
+    }
+}
+
+impl<S, E, C> TraceHandle<S, E, C>
+where
+    S: State<Context = C, Error = E>,
+    E: Clone,
+{
+    /// Create a handle retaining up to `window` progress items
+    pub(crate) fn new(window: usize) -> Self {
+        Self {
+            window,
+            history: Arc::default(),
+        }
+    }
+
+    /// Record an emitted progress item, dropping the oldest one if the window is full
+    pub(crate) fn record(&self, progress: &Progress<S, E, C>) {
+        if self.window == 0 {
+            return;
+        }
+
+        let mut history = self.history.lock().unwrap();
+
+        if history.len() == self.window {
+            history.pop_front();
+        }
+
+        history.push_back(progress.clone());
+    }
+
+    /// The retained progress items, oldest first
+    pub fn history(&self) -> Vec<Progress<S, E, C>> {
+        self.history.lock().unwrap().iter().cloned().collect()
+    }
+}
@@ -0,0 +1,79 @@
+use crate::state::State;
+use async_trait::async_trait;
+
+/// An alternative to `State` for machines that describe their changes to the `Context` as
+/// explicit updates rather than mutating it directly. Transitions receive read-only access to the
+/// context and return an (optional) update alongside the next state, which the framework folds
+/// into the context through `apply_update`. This makes every context mutation explicit and
+/// auditable, and removes the need for interior mutability patterns like `Arc<Mutex<_>>`.
+///
+/// `UpdatingState`s are run by wrapping them in `Updating`, which implements `State`.
+#[async_trait(?Send)]
+pub trait UpdatingState: Clone + PartialEq {
+    /// Global state shared between all `Streamline` states.
+    type Context;
+    /// The Error shared between all states progressions.
+    type Error;
+    /// A change to the `Context` produced by a transition
+    type ContextUpdate;
+
+    /// Derives the next state (with the same semantics as `State::next`), along with an optional
+    /// update to apply to the context before moving to that state
+    async fn next(
+        &self,
+        context: Option<&Self::Context>,
+    ) -> Result<(Option<Self>, Option<Self::ContextUpdate>), Self::Error>;
+
+    /// Derives the previous state on reversion (with the same semantics as `State::revert`),
+    /// along with an optional update to apply to the context. By default, `revert` simply ends
+    /// the `Streamline` without updating the context. Only available with the `revert` feature.
+    #[cfg(feature = "revert")]
+    async fn revert(
+        &self,
+        _context: Option<&Self::Context>,
+    ) -> Result<(Option<Self>, Option<Self::ContextUpdate>), Self::Error> {
+        Ok((None, None))
+    }
+
+    /// Fold an update into the context. Updates are only applied when the `Streamline` has a
+    /// context, and are discarded otherwise.
+    fn apply_update(context: &mut Self::Context, update: Self::ContextUpdate);
+}
+
+/// Adapter that runs an `UpdatingState` as a `State`, applying context updates after each
+/// transition
+#[derive(Clone, Debug, PartialEq)]
+pub struct Updating<S>(pub S);
+
+#[async_trait(?Send)]
+impl<S> State for Updating<S>
+where
+    S: UpdatingState,
+{
+    type Context = S::Context;
+    type Error = S::Error;
+
+    async fn next(&self, context: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+        let (next, update) = self.0.next(context.as_deref()).await?;
+
+        if let (Some(context), Some(update)) = (context, update) {
+            S::apply_update(context, update);
+        }
+
+        Ok(next.map(Self))
+    }
+
+    #[cfg(feature = "revert")]
+    async fn revert(
+        &self,
+        context: Option<&mut Self::Context>,
+    ) -> Result<Option<Self>, Self::Error> {
+        let (previous, update) = self.0.revert(context.as_deref()).await?;
+
+        if let (Some(context), Some(update)) = (context, update) {
+            S::apply_update(context, update);
+        }
+
+        Ok(previous.map(Self))
+    }
+}
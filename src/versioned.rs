@@ -0,0 +1,110 @@
+use std::{
+    error::Error,
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+/// A context wrapper that counts mutable accesses, for detecting contexts modified outside of
+/// a `Streamline` (e.g. through `SharedContext::with`) while it runs. The version starts at `0`
+/// and is bumped every time the context is dereferenced mutably (whether or not it actually
+/// changes), so a version that moves between two transitions means that something else had
+/// mutable access to the context in the meantime. Read-only access never bumps the version.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Versioned<C> {
+    inner: C,
+    version: u64,
+}
+
+impl<C> Versioned<C> {
+    /// Wrap a context, starting at version `0`
+    pub fn new(inner: C) -> Self {
+        Self { inner, version: 0 }
+    }
+
+    /// The number of times the context has been dereferenced mutably
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Unwrap the context
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+}
+
+impl<C> Deref for Versioned<C> {
+    type Target = C;
+
+    fn deref(&self) -> &C {
+        &self.inner
+    }
+}
+
+impl<C> DerefMut for Versioned<C> {
+    fn deref_mut(&mut self) -> &mut C {
+        self.version = self.version.wrapping_add(1);
+
+        &mut self.inner
+    }
+}
+
+/// The error used to start reverting a `Streamline` built with
+/// `Streamline::revert_on_context_modification` when its `Versioned` context was modified
+/// outside of the `Streamline` between two transitions
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ContextModified {
+    /// the version of the context at the end of the previous transition
+    pub expected: u64,
+    /// the version of the context at the start of the next transition
+    pub found: u64,
+}
+
+impl fmt::Display for ContextModified {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "context was modified outside of the Streamline (expected version {}, found {})",
+            self.expected, self.found
+        )
+    }
+}
+
+impl Error for ContextModified {}
+
+/// Tracks the version of a `Versioned` context between the transitions of a `Streamline`
+pub(crate) struct VersionCheck<C, E> {
+    version: fn(&C) -> u64,
+    error: Option<fn(ContextModified) -> E>,
+    last: Option<u64>,
+}
+
+impl<C, E> VersionCheck<C, E> {
+    /// Check versions with `version`, reporting modifications as warnings if there is no `error`
+    pub(crate) fn new(version: fn(&C) -> u64, error: Option<fn(ContextModified) -> E>) -> Self {
+        Self {
+            version,
+            error,
+            last: None,
+        }
+    }
+
+    /// Record the version of the context at the end of a transition
+    pub(crate) fn record(&mut self, context: Option<&C>) {
+        self.last = context.map(self.version);
+    }
+
+    /// Compare the version of the context at the start of a transition with the last recorded
+    /// version, returning the modification (if any) along with the error it should revert with
+    pub(crate) fn check(&self, context: Option<&C>) -> Option<(ContextModified, Option<E>)> {
+        let expected = self.last?;
+        let found = (self.version)(context?);
+
+        if found == expected {
+            return None;
+        }
+
+        let modified = ContextModified { expected, found };
+
+        Some((modified, self.error.map(|error| error(modified))))
+    }
+}
@@ -0,0 +1,79 @@
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+
+/// Durable, ordered record of the states reached by a `Streamline` (see `Streamline::with_wal`),
+/// for resuming from the last recorded state after a crash
+#[async_trait(?Send)]
+pub trait WriteAheadLog<S, E> {
+    /// Durably record that the `Streamline` has transitioned to `state`, returning once the
+    /// record is durable. Errors are handled like errors returned from `State::next`.
+    async fn append(&self, state: &S) -> Result<(), E>;
+}
+
+#[async_trait(?Send)]
+impl<S, E, T> WriteAheadLog<S, E> for Arc<T>
+where
+    T: WriteAheadLog<S, E> + ?Sized,
+    S: 'static,
+{
+    async fn append(&self, state: &S) -> Result<(), E> {
+        (**self).append(state).await
+    }
+}
+
+/// A simple in-memory `WriteAheadLog`, mostly useful for testing. Wrap it in an `Arc` to read the
+/// recorded states back while (or after) a `Streamline` runs.
+#[derive(Debug)]
+pub struct MemoryWal<S> {
+    entries: Mutex<Vec<S>>,
+}
+
+impl<S> Default for MemoryWal<S> {
+    fn default() -> Self {
+        Self {
+            entries: Mutex::new(vec![]),
+        }
+    }
+}
+
+impl<S> MemoryWal<S>
+where
+    S: Clone,
+{
+    /// Create an empty `MemoryWal`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every recorded state, oldest first
+    pub fn entries(&self) -> Vec<S> {
+        self.entries
+            .lock()
+            .expect("could not get lock on write-ahead log")
+            .clone()
+    }
+
+    /// The most recently recorded state, if any, to resume from
+    pub fn last(&self) -> Option<S> {
+        self.entries
+            .lock()
+            .expect("could not get lock on write-ahead log")
+            .last()
+            .cloned()
+    }
+}
+
+#[async_trait(?Send)]
+impl<S, E> WriteAheadLog<S, E> for MemoryWal<S>
+where
+    S: Clone + 'static,
+{
+    async fn append(&self, state: &S) -> Result<(), E> {
+        self.entries
+            .lock()
+            .expect("could not get lock on write-ahead log")
+            .push(state.clone());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use futures::StreamExt;
+use streamline::{Progress, RevertAborted, RevertProgress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn aborts_reversion() {
+    #[derive(Debug, PartialEq)]
+    struct Context;
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        Middle,
+        End,
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum MyError {
+        Failed,
+        Aborted,
+    }
+
+    impl From<RevertAborted> for MyError {
+        fn from(_: RevertAborted) -> Self {
+            Self::Aborted
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = Context;
+        type Error = MyError;
+
+        async fn next(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::Start => Ok(Some(Self::Middle)),
+                MyState::Middle => Ok(Some(Self::End)),
+                MyState::End => Err(MyError::Failed),
+            }
+        }
+
+        async fn revert(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            let next_state = match self {
+                MyState::End => Some(Self::Middle),
+                MyState::Middle => Some(Self::Start),
+                MyState::Start => None,
+            };
+
+            Ok(next_state)
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let (streamline, abort) = Streamline::build(MyState::Start)
+            .context(Context)
+            .abortable();
+
+        let mut stream = Box::pin(streamline.run());
+
+        while let Some(progress) = stream.next().await {
+            if let Progress::Revert(RevertProgress::Reverting { .. }) = progress {
+                break;
+            }
+        }
+
+        abort.abort_revert().expect("could not abort reversion");
+
+        let remaining: Vec<_> = stream.collect().await;
+
+        match remaining.as_slice() {
+            [Progress::Revert(RevertProgress::Reverting { step, .. }), Progress::Revert(RevertProgress::Failure {
+                source: Some(source),
+                error,
+            })] => {
+                assert_eq!(step, &MyState::Middle);
+                assert_eq!(**source, MyError::Failed);
+                assert_eq!(error, &MyError::Aborted);
+            }
+            _ => panic!("incorrect terminal states found"),
+        }
+    });
+}
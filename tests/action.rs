@@ -0,0 +1,171 @@
+#![cfg(feature = "revert")]
+use futures::{FutureExt, StreamExt};
+use futures_timer::Delay;
+use std::{cell::RefCell, rc::Rc, time::Duration};
+use streamline::{
+    Action, Progress, RetryPolicy, RevertProgress, StepPolicy, StepTimedOut, Streamline,
+};
+use tokio::runtime::Runtime;
+
+#[test]
+fn runs_single_actions() {
+    #[derive(Clone, Debug, PartialEq)]
+    struct Context {
+        log: Vec<&'static str>,
+    }
+
+    fn streamline(
+        fail: bool,
+    ) -> Streamline<Context, &'static str, streamline::Action<Context, &'static str>> {
+        Streamline::from_action(
+            move |context: Option<&mut Context>| {
+                async move {
+                    context.ok_or("Missing context")?.log.push("action");
+
+                    if fail {
+                        Err("Something went wrong!")
+                    } else {
+                        Ok(())
+                    }
+                }
+                .boxed_local()
+            },
+            |context: Option<&mut Context>| {
+                async move {
+                    context.ok_or("Missing context")?.log.push("compensate");
+
+                    Ok(())
+                }
+                .boxed_local()
+            },
+        )
+        .context(Context { log: vec![] })
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let completed: Vec<_> = streamline(false).run_with_snapshots().collect().await;
+
+        match completed.as_slice() {
+            [(Progress::Ok(pending), _), (Progress::Ok(done), Some(context))] => {
+                assert!(!pending.is_done());
+                assert!(done.is_done());
+                assert_eq!(context.log, ["action"]);
+            }
+            _ => panic!("incorrect progress found"),
+        }
+
+        let reverted: Vec<_> = streamline(true).run_with_snapshots().collect().await;
+
+        match reverted.as_slice() {
+            [(Progress::Ok(_), _), (Progress::Revert(RevertProgress::Reverting { .. }), _), (
+                Progress::Revert(RevertProgress::Reverted {
+                    source: Some(source),
+                    ..
+                }),
+                Some(context),
+            )] => {
+                assert_eq!(**source, "Something went wrong!");
+                assert_eq!(context.log, ["action", "compensate"]);
+            }
+            _ => panic!("incorrect progress found"),
+        }
+    });
+}
+
+#[test]
+fn retries_actions() {
+    #[derive(Clone, Debug, PartialEq)]
+    struct Context {
+        attempts: u32,
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct MyError(StepTimedOut);
+
+    impl From<StepTimedOut> for MyError {
+        fn from(error: StepTimedOut) -> Self {
+            Self(error)
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let completed: Vec<_> = Streamline::from_action(
+            |context: Option<&mut Context>| {
+                async move {
+                    let context = context.expect("missing context");
+
+                    context.attempts += 1;
+
+                    if context.attempts < 2 {
+                        Delay::new(Duration::from_secs(10)).await;
+                    }
+
+                    Ok(())
+                }
+                .boxed_local()
+            },
+            |_: Option<&mut Context>| async { Ok::<_, MyError>(()) }.boxed_local(),
+        )
+        .context(Context { attempts: 0 })
+        .step_policy(StepPolicy {
+            timeout: Some(Duration::from_millis(10)),
+            retries: RetryPolicy::new(1),
+        })
+        .run_with_snapshots()
+        .collect()
+        .await;
+
+        match completed.as_slice() {
+            [(Progress::Ok(pending), _), (Progress::Ok(done), Some(context))] => {
+                assert!(!pending.is_done());
+                assert!(done.is_done());
+                assert_eq!(context.attempts, 2);
+            }
+            _ => panic!("incorrect progress found"),
+        }
+    });
+}
+
+#[test]
+fn skips_compensation_before_running() {
+    fn streamline(log: Rc<RefCell<Vec<&'static str>>>) -> Streamline<(), (), Action<(), ()>> {
+        let compensations = log.clone();
+
+        Streamline::from_action(
+            move |_: Option<&mut ()>| {
+                log.borrow_mut().push("action");
+
+                async { Ok(()) }.boxed_local()
+            },
+            move |_: Option<&mut ()>| {
+                compensations.borrow_mut().push("compensate");
+
+                async { Ok(()) }.boxed_local()
+            },
+        )
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let log = Rc::new(RefCell::new(vec![]));
+        let (stream, cancellation_handle) = streamline(log.clone()).run_preemptible();
+
+        cancellation_handle
+            .cancel()
+            .expect("could not send value through channel");
+
+        let cancelled: Vec<_> = stream.collect().await;
+
+        match cancelled.last() {
+            Some(Progress::Revert(RevertProgress::Reverted { source: None, .. })) => {}
+            _ => panic!("incorrect terminal state found"),
+        }
+
+        assert!(log.borrow().is_empty());
+
+        // the states of different machines never compare equal
+        let first: Vec<_> = streamline(log.clone()).run().collect().await;
+        let second: Vec<_> = streamline(log).run().collect().await;
+
+        assert_ne!(first[0], second[0]);
+    });
+}
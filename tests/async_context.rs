@@ -0,0 +1,78 @@
+#![cfg(feature = "tokio")]
+use async_trait::async_trait;
+use futures::{future, StreamExt};
+use std::task::Poll;
+use streamline::{AsyncContext, State, Streamline, Supervisor};
+use tokio::runtime::Runtime;
+
+#[test]
+fn holds_context_across_await() {
+    struct Context {
+        items: Vec<u8>,
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start(u8),
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = AsyncContext<Context>;
+        type Error = ();
+
+        async fn next(
+            &self,
+            context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            let mut context = context.ok_or(())?.write().await;
+
+            // yield to the other machine while still holding the guard
+            let mut yielded = false;
+
+            future::poll_fn(|cx| {
+                if yielded {
+                    Poll::Ready(())
+                } else {
+                    yielded = true;
+                    cx.waker().wake_by_ref();
+
+                    Poll::Pending
+                }
+            })
+            .await;
+
+            let next_state = match self {
+                MyState::Start(item) => {
+                    context.items.push(*item);
+
+                    Some(Self::End)
+                }
+                MyState::End => None,
+            };
+
+            Ok(next_state)
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let context = AsyncContext::new(Context { items: vec![] });
+
+        let mut supervisor = Supervisor::new();
+
+        for item in 0..2 {
+            supervisor.add(Streamline::build(MyState::Start(item)).context(context.clone()));
+        }
+
+        let (stream, _cancel_all) = supervisor.run_all();
+
+        stream.collect::<Vec<_>>().await;
+
+        let mut items = context.read().await.items.clone();
+
+        items.sort_unstable();
+
+        assert_eq!(items, [0, 1]);
+    });
+}
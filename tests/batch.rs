@@ -0,0 +1,154 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::cell::RefCell;
+use streamline::{BatchState, Progress, RevertProgress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn compensates_partial_batches() {
+    #[derive(Clone, Debug, PartialEq)]
+    struct Context {
+        created: RefCell<Vec<u32>>,
+        reverted: Vec<&'static str>,
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start(u32),
+        Create(u32),
+        Created(Vec<u32>),
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = Context;
+        type Error = &'static str;
+
+        async fn next(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::Start(size) => Ok(Some(Self::Create(*size))),
+                MyState::Create(_) => unreachable!("batches run through next_item"),
+                MyState::Created(_) => Ok(None),
+            }
+        }
+
+        async fn revert(
+            &self,
+            context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            let context = context.ok_or("Missing context")?;
+
+            match self {
+                MyState::Create(size) => {
+                    context.reverted.push("Create");
+
+                    Ok(Some(Self::Start(*size)))
+                }
+                _ => Ok(None),
+            }
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl BatchState for MyState {
+        type Item = u32;
+        type Handle = u32;
+
+        fn batch(&self) -> Option<Vec<Self::Item>> {
+            match self {
+                MyState::Create(size) => Some((0..*size).collect()),
+                _ => None,
+            }
+        }
+
+        async fn next_item(
+            &self,
+            item: Self::Item,
+            context: Option<&Self::Context>,
+        ) -> Result<Self::Handle, Self::Error> {
+            let context = context.ok_or("Missing context")?;
+
+            match item {
+                3 => Err("Could not create item"),
+                item => {
+                    context.created.borrow_mut().push(item);
+
+                    Ok(item)
+                }
+            }
+        }
+
+        fn batch_completed(&self, handles: Vec<Self::Handle>) -> Option<Self> {
+            Some(Self::Created(handles))
+        }
+
+        async fn revert_items(
+            &self,
+            handles: Vec<Self::Handle>,
+            context: Option<&mut Self::Context>,
+        ) -> Result<(), Self::Error> {
+            let context = context.ok_or("Missing context")?;
+
+            context
+                .created
+                .get_mut()
+                .retain(|item| !handles.contains(item));
+            context.reverted.push("items");
+
+            Ok(())
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let snapshots: Vec<_> = Streamline::build(MyState::Start(3))
+            .context(Context {
+                created: RefCell::new(vec![]),
+                reverted: vec![],
+            })
+            .batched()
+            .run_with_snapshots()
+            .collect()
+            .await;
+
+        assert_eq!(
+            snapshots.last(),
+            Some(&(
+                Progress::Ok(MyState::Created(vec![0, 1, 2])),
+                Some(Context {
+                    created: RefCell::new(vec![0, 1, 2]),
+                    reverted: vec![],
+                })
+            ))
+        );
+
+        let snapshots: Vec<_> = Streamline::build(MyState::Start(5))
+            .context(Context {
+                created: RefCell::new(vec![]),
+                reverted: vec![],
+            })
+            .batched()
+            .run_with_snapshots()
+            .collect()
+            .await;
+
+        match snapshots.last() {
+            Some((
+                Progress::Revert(RevertProgress::Reverted {
+                    source: Some(source),
+                    compensated_states,
+                }),
+                Some(context),
+            )) => {
+                assert_eq!(**source, "Could not create item");
+                assert_eq!(compensated_states, &[MyState::Create(5)]);
+                assert!(context.created.borrow().is_empty());
+                assert_eq!(context.reverted, ["items", "Create"]);
+            }
+            _ => panic!("incorrect terminal state found"),
+        }
+    });
+}
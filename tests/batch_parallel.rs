@@ -0,0 +1,154 @@
+#[cfg(feature = "revert")]
+use async_trait::async_trait;
+use futures::future;
+#[cfg(feature = "revert")]
+use futures::StreamExt;
+use std::{cell::Cell, task::Poll};
+use streamline::next_batch_parallel;
+#[cfg(feature = "revert")]
+use streamline::{BatchState, Progress, State, Streamline};
+use tokio::runtime::Runtime;
+
+/// Yield once, so that other items get a chance to start
+async fn yield_once() {
+    let mut yielded = false;
+
+    future::poll_fn(|cx| {
+        if yielded {
+            Poll::Ready(())
+        } else {
+            yielded = true;
+            cx.waker().wake_by_ref();
+
+            Poll::Pending
+        }
+    })
+    .await
+}
+
+#[test]
+fn caps_parallelism() {
+    Runtime::new().unwrap().block_on(async {
+        let in_flight = Cell::new(0);
+        let max_in_flight = Cell::new(0);
+
+        let batch = next_batch_parallel(0..10u32, 3, |item| {
+            let in_flight = &in_flight;
+            let max_in_flight = &max_in_flight;
+
+            async move {
+                in_flight.set(in_flight.get() + 1);
+                max_in_flight.set(max_in_flight.get().max(in_flight.get()));
+
+                yield_once().await;
+
+                in_flight.set(in_flight.get() - 1);
+
+                match item {
+                    7 => Err("Could not create item"),
+                    item => Ok(item),
+                }
+            }
+        })
+        .await;
+
+        let (error, mut handles) = batch.expect_err("batch should fail");
+
+        handles.sort_unstable();
+
+        assert_eq!(error, "Could not create item");
+        assert_eq!(handles, [0, 1, 2, 3, 4, 5, 6]);
+        assert_eq!(max_in_flight.get(), 3);
+    });
+}
+
+#[test]
+#[cfg(feature = "revert")]
+fn caps_parallelism_of_batched_streamlines() {
+    #[derive(Clone, Debug, Default, PartialEq)]
+    struct Context {
+        in_flight: Cell<u32>,
+        max_in_flight: Cell<u32>,
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Create,
+        Created(usize),
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = Context;
+        type Error = &'static str;
+
+        async fn next(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            Ok(None)
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl BatchState for MyState {
+        type Item = u32;
+        type Handle = u32;
+
+        fn batch(&self) -> Option<Vec<Self::Item>> {
+            match self {
+                MyState::Create => Some((0..10).collect()),
+                MyState::Created(_) => None,
+            }
+        }
+
+        async fn next_item(
+            &self,
+            item: Self::Item,
+            context: Option<&Self::Context>,
+        ) -> Result<Self::Handle, Self::Error> {
+            let context = context.ok_or("Missing context")?;
+
+            context.in_flight.set(context.in_flight.get() + 1);
+            context
+                .max_in_flight
+                .set(context.max_in_flight.get().max(context.in_flight.get()));
+
+            yield_once().await;
+
+            context.in_flight.set(context.in_flight.get() - 1);
+
+            Ok(item)
+        }
+
+        fn batch_completed(&self, handles: Vec<Self::Handle>) -> Option<Self> {
+            Some(Self::Created(handles.len()))
+        }
+
+        async fn revert_items(
+            &self,
+            _handles: Vec<Self::Handle>,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let snapshots: Vec<_> = Streamline::build(MyState::Create)
+            .context(Context::default())
+            .batched()
+            .max_parallelism(3)
+            .run_with_snapshots()
+            .collect()
+            .await;
+
+        match snapshots.last() {
+            Some((Progress::Ok(MyState::Created(created)), Some(context))) => {
+                assert_eq!(*created, 10);
+                assert_eq!(context.max_in_flight.get(), 3);
+            }
+            _ => panic!("incorrect terminal state found"),
+        }
+    });
+}
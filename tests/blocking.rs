@@ -0,0 +1,38 @@
+#![cfg(feature = "blocking")]
+use async_trait::async_trait;
+use streamline::{Progress, State, Streamline};
+
+#[test]
+fn collects_without_runtime() {
+    #[derive(Debug, PartialEq)]
+    struct Context;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Countdown(u8);
+
+    #[async_trait(?Send)]
+    impl State for Countdown {
+        type Context = Context;
+        type Error = ();
+
+        async fn next(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            Ok(self.0.checked_sub(1).map(Self))
+        }
+    }
+
+    let states = Streamline::build(Countdown(2))
+        .context(Context)
+        .collect_blocking();
+
+    assert_eq!(
+        states,
+        [
+            Progress::Ok(Countdown(2)),
+            Progress::Ok(Countdown(1)),
+            Progress::Ok(Countdown(0)),
+        ]
+    );
+}
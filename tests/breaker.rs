@@ -0,0 +1,82 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use futures::StreamExt;
+use streamline::{CircuitBreaker, CircuitOpen, Progress, RevertProgress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn trips_after_repeated_failures() {
+    #[derive(Debug, PartialEq)]
+    enum MyError {
+        Unavailable,
+        CircuitOpen,
+    }
+
+    impl From<CircuitOpen> for MyError {
+        fn from(_: CircuitOpen) -> Self {
+            MyError::CircuitOpen
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start { available: bool },
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = ();
+        type Error = MyError;
+
+        async fn next(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::Start { available: true } => Ok(Some(MyState::End)),
+                MyState::Start { available: false } => Err(MyError::Unavailable),
+                MyState::End => Ok(None),
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let breaker = CircuitBreaker::new(2);
+
+        let run = |available| {
+            Streamline::build(MyState::Start { available })
+                .with_breaker(breaker.clone())
+                .run()
+                .collect::<Vec<_>>()
+        };
+
+        run(false).await;
+        run(true).await;
+        run(false).await;
+
+        assert_eq!(breaker.failures(), 1);
+        assert!(!breaker.is_open());
+
+        run(false).await;
+
+        assert!(breaker.is_open());
+
+        assert_eq!(
+            run(true).await,
+            [Progress::Revert(RevertProgress::Failure {
+                source: None,
+                error: MyError::CircuitOpen,
+            })]
+        );
+
+        assert_eq!(breaker.failures(), 2);
+
+        breaker.reset();
+
+        assert_eq!(
+            run(true).await,
+            [
+                Progress::Ok(MyState::Start { available: true }),
+                Progress::Ok(MyState::End),
+            ]
+        );
+    });
+}
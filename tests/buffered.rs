@@ -0,0 +1,61 @@
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::{cell::Cell, rc::Rc};
+use streamline::{Progress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn buffers_ahead_of_consumer() {
+    #[derive(Clone, Debug, PartialEq)]
+    struct Context {
+        transitions: Rc<Cell<u32>>,
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct MyState(u32);
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = Context;
+        type Error = ();
+
+        async fn next(
+            &self,
+            context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            let transitions = &context.ok_or(())?.transitions;
+
+            transitions.set(transitions.get() + 1);
+
+            match self.0 {
+                100 => Ok(None),
+                count => Ok(Some(Self(count + 1))),
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let transitions = Rc::new(Cell::new(0));
+
+        let mut stream = Box::pin(
+            Streamline::build(MyState(0))
+                .context(Context {
+                    transitions: transitions.clone(),
+                })
+                .buffered(4),
+        );
+
+        assert_eq!(stream.next().await, Some(Progress::Ok(MyState(0))));
+
+        let buffered = transitions.get();
+
+        // the producer stops shortly after filling the buffer
+        assert!((4..=6).contains(&buffered));
+
+        let rest: Vec<_> = stream.collect().await;
+
+        assert_eq!(rest.len(), 100);
+        assert_eq!(rest.last(), Some(&Progress::Ok(MyState(100))));
+        assert_eq!(transitions.get(), 101);
+    });
+}
@@ -1,5 +1,6 @@
+#![cfg(feature = "revert")]
 use async_trait::async_trait;
-use futures::StreamExt;
+use futures::{FutureExt, StreamExt};
 use streamline::{Progress, RevertProgress, State, Streamline};
 use tokio::runtime::Runtime;
 
@@ -73,10 +74,96 @@ fn cancels() {
         }
 
         match last_step {
-            Progress::Revert(RevertProgress::Reverted { source }) => {
+            Progress::Revert(RevertProgress::Reverted { source, .. }) => {
                 assert_eq!(source, None)
             }
             _ => panic!("incorrect terminal state found"),
         }
     });
 }
+
+#[test]
+fn acknowledges_cancellation() {
+    #[derive(Debug)]
+    struct Context;
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        Middle(String),
+        End(String),
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct MyError(&'static str);
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = Context;
+        type Error = MyError;
+
+        async fn next(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            let next_state = match self {
+                MyState::Start => Some(Self::Middle("hooray!".into())),
+                MyState::Middle(content) => Some(Self::End(content.into())),
+                _ => None,
+            };
+
+            Ok(next_state)
+        }
+
+        async fn revert(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            let next_state = match self {
+                MyState::End(content) => Some(Self::Middle(content.to_string())),
+                MyState::Middle(_) => Some(Self::Start),
+                _ => None,
+            };
+
+            Ok(next_state)
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let (streamline, cancellation_handle) = Streamline::build(MyState::Start)
+            .context(Context)
+            .run_preemptible();
+
+        let mut stream = streamline.boxed_local();
+
+        match stream.next().await {
+            Some(Progress::Ok(state)) => assert_eq!(&state, &MyState::Start),
+            _ => panic!("incorrect start state found"),
+        };
+
+        let acknowledgement = cancellation_handle.cancel_and_wait();
+
+        futures::pin_mut!(acknowledgement);
+
+        assert!((&mut acknowledgement).now_or_never().is_none());
+
+        stream.next().await;
+
+        acknowledgement.await;
+
+        match stream.next().await {
+            Some(Progress::CancellationRequested { step }) => {
+                assert_eq!(step, MyState::Middle("hooray!".into()))
+            }
+            _ => panic!("missing cancellation request"),
+        }
+
+        match stream.next().await {
+            Some(Progress::Revert(RevertProgress::Reverting { step, source })) => {
+                assert_eq!(step, MyState::Middle("hooray!".into()));
+                assert_eq!(source, None);
+            }
+            _ => panic!("incorrect reverting state found"),
+        }
+    });
+}
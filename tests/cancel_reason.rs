@@ -0,0 +1,95 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use futures::{future, StreamExt};
+use streamline::{Progress, RevertProgress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn cancels_with_typed_reasons() {
+    #[derive(Debug, PartialEq)]
+    enum Reason {
+        Shutdown,
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = ();
+        type Error = ();
+
+        async fn next(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::Start => Ok(Some(MyState::End)),
+                MyState::End => Ok(None),
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let (stream, cancel) = Streamline::build(MyState::Start).run_preemptible_with_reason();
+
+        cancel.cancel_with_reason(Reason::Shutdown).unwrap();
+
+        let states: Vec<_> = stream.collect().await;
+
+        assert_eq!(
+            states,
+            [
+                Progress::Ok(MyState::Start),
+                Progress::CancellationRequested {
+                    step: MyState::Start
+                },
+                Progress::Revert(RevertProgress::Cancelled {
+                    reason: Reason::Shutdown,
+                    step: MyState::Start,
+                }),
+                Progress::Revert(RevertProgress::Reverting {
+                    step: MyState::Start,
+                    source: None,
+                }),
+                Progress::Revert(RevertProgress::Reverted {
+                    source: None,
+                    compensated_states: vec![],
+                }),
+            ]
+        );
+    });
+}
+
+#[test]
+fn waits_for_typed_cancellations() {
+    #[derive(Clone, Debug, PartialEq)]
+    struct MyState;
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = ();
+        type Error = ();
+
+        async fn next(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+            Ok(Some(MyState))
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let (stream, cancel) = Streamline::build(MyState).run_preemptible_with_reason();
+
+        let (states, _) = future::join(
+            stream.collect::<Vec<_>>(),
+            cancel.cancel_with_reason_and_wait("shutting down"),
+        )
+        .await;
+
+        assert!(
+            states.contains(&Progress::Revert(RevertProgress::Cancelled {
+                reason: "shutting down",
+                step: MyState,
+            }))
+        );
+    });
+}
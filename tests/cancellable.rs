@@ -0,0 +1,81 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use futures::StreamExt;
+use streamline::{Progress, RevertProgress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn defers_cancellation_until_cancellable() {
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        Locked,
+        Committed,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = ();
+        type Error = ();
+
+        async fn next(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+            let next_state = match self {
+                MyState::Start => Some(Self::Locked),
+                MyState::Locked => Some(Self::Committed),
+                MyState::Committed => Some(Self::End),
+                MyState::End => None,
+            };
+
+            Ok(next_state)
+        }
+
+        async fn revert(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+            let next_state = match self {
+                MyState::End => Some(Self::Committed),
+                MyState::Committed => Some(Self::Locked),
+                MyState::Locked => Some(Self::Start),
+                MyState::Start => None,
+            };
+
+            Ok(next_state)
+        }
+
+        fn is_cancellable(&self) -> bool {
+            !matches!(self, MyState::Locked | MyState::Committed)
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let (streamline, cancel) = Streamline::build(MyState::Start).run_preemptible();
+
+        let mut stream = streamline.boxed_local();
+
+        let mut states = vec![stream.next().await.unwrap()];
+
+        cancel.cancel().unwrap();
+
+        states.extend(stream.collect::<Vec<_>>().await);
+
+        let reverting = |step| Progress::Revert(RevertProgress::Reverting { step, source: None });
+
+        assert_eq!(
+            states,
+            [
+                Progress::Ok(MyState::Start),
+                Progress::Ok(MyState::Locked),
+                Progress::Ok(MyState::Committed),
+                Progress::Ok(MyState::End),
+                Progress::CancellationRequested { step: MyState::End },
+                reverting(MyState::End),
+                reverting(MyState::Committed),
+                reverting(MyState::Locked),
+                reverting(MyState::Start),
+                Progress::Revert(RevertProgress::Reverted {
+                    source: None,
+                    compensated_states: vec![MyState::End, MyState::Committed, MyState::Locked],
+                }),
+            ]
+        );
+    });
+}
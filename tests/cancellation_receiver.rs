@@ -0,0 +1,62 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use futures::{FutureExt, StreamExt};
+use streamline::{Progress, RevertProgress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn drives_cancellation_externally() {
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = ();
+        type Error = ();
+
+        async fn next(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::Start => Ok(Some(MyState::End)),
+                MyState::End => Ok(None),
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let (mut streamline, cancel) = Streamline::build(MyState::Start).preemptible();
+
+        let receiver = streamline
+            .take_cancellation_receiver()
+            .expect("missing cancellation receiver");
+
+        assert!(streamline.take_cancellation_receiver().is_none());
+
+        cancel.cancel_and_wait().await;
+
+        let states: Vec<_> = streamline
+            .run_with_cancel(receiver.map(|_| ()))
+            .collect()
+            .await;
+
+        assert_eq!(
+            states,
+            [
+                Progress::Ok(MyState::Start),
+                Progress::CancellationRequested {
+                    step: MyState::Start
+                },
+                Progress::Revert(RevertProgress::Reverting {
+                    step: MyState::Start,
+                    source: None,
+                }),
+                Progress::Revert(RevertProgress::Reverted {
+                    source: None,
+                    compensated_states: vec![],
+                }),
+            ]
+        );
+    });
+}
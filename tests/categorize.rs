@@ -0,0 +1,103 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::cell::Cell;
+use streamline::{ErrorCategory, Progress, RetryPolicy, RevertProgress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[derive(Debug, PartialEq)]
+enum MyError {
+    Flaky,
+    Fatal,
+}
+
+#[derive(Debug, PartialEq)]
+struct Context {
+    attempts: Cell<u32>,
+    reverted: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum MyState {
+    Start,
+    Flaky,
+    Fatal,
+}
+
+#[async_trait(?Send)]
+impl State for MyState {
+    type Context = Context;
+    type Error = MyError;
+
+    async fn next(&self, context: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+        let context = context.unwrap();
+
+        match self {
+            MyState::Start => Ok(Some(MyState::Flaky)),
+            MyState::Flaky => {
+                let attempts = context.attempts.get() + 1;
+
+                context.attempts.set(attempts);
+
+                if attempts < 3 {
+                    Err(MyError::Flaky)
+                } else {
+                    Ok(Some(MyState::Fatal))
+                }
+            }
+            MyState::Fatal => Err(MyError::Fatal),
+        }
+    }
+
+    async fn revert(
+        &self,
+        context: Option<&mut Self::Context>,
+    ) -> Result<Option<Self>, Self::Error> {
+        context.unwrap().reverted = true;
+
+        Ok(None)
+    }
+
+    fn categorize(&self, error: &Self::Error) -> ErrorCategory {
+        match error {
+            MyError::Flaky => ErrorCategory::Retry,
+            MyError::Fatal => ErrorCategory::Abort,
+        }
+    }
+}
+
+#[test]
+fn retries_and_aborts_by_category() {
+    Runtime::new().unwrap().block_on(async {
+        let mut context = Some(Context {
+            attempts: Cell::new(0),
+            reverted: false,
+        });
+
+        let states = Streamline::build(MyState::Start)
+            .context(context.take().unwrap())
+            .retry(RetryPolicy::new(2))
+            .finally(move |_, context| {
+                let context = context.unwrap();
+
+                assert_eq!(context.attempts.get(), 3);
+                assert!(!context.reverted);
+            })
+            .run()
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(
+            states,
+            vec![
+                Progress::Ok(MyState::Start),
+                Progress::Ok(MyState::Flaky),
+                Progress::Ok(MyState::Fatal),
+                Progress::Revert(RevertProgress::Failure {
+                    source: None,
+                    error: MyError::Fatal,
+                }),
+            ]
+        );
+    });
+}
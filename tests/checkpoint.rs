@@ -0,0 +1,128 @@
+#![cfg(feature = "serde")]
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use streamline::{Progress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn restores_checkpoints() {
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct Context {
+        items: Vec<u8>,
+    }
+
+    #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+    enum MyState {
+        Start,
+        Middle(String),
+        End(String),
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = Context;
+        type Error = ();
+
+        async fn next(
+            &self,
+            context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            context.ok_or(())?.items.push(0);
+
+            let next_state = match self {
+                MyState::Start => Some(Self::Middle("hooray!".into())),
+                MyState::Middle(content) => Some(Self::End(content.into())),
+                _ => None,
+            };
+
+            Ok(next_state)
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let streamline = Streamline::build(MyState::Middle("hooray!".into()))
+            .context(Context { items: vec![1] });
+
+        let checkpoint = serde_json::to_string(&streamline).expect("could not serialize");
+
+        assert_eq!(
+            checkpoint,
+            r#"{"context":{"items":[1]},"current":{"Ok":{"Middle":"hooray!"}},"pending":[]}"#
+        );
+
+        let restored: Streamline<Context, (), MyState> =
+            serde_json::from_str(&checkpoint).expect("could not deserialize");
+
+        let states: Vec<_> = restored.run().collect().await;
+
+        assert_eq!(
+            states,
+            [
+                Progress::Ok(MyState::Middle("hooray!".into())),
+                Progress::Ok(MyState::End("hooray!".into())),
+            ]
+        );
+    });
+}
+
+#[test]
+fn resumes_part_way_through() {
+    #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+    struct Context {
+        items: Vec<u8>,
+    }
+
+    #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+    enum MyState {
+        Start,
+        Middle,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = Context;
+        type Error = ();
+
+        async fn next(
+            &self,
+            context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            let context = context.ok_or(())?;
+
+            let next_state = match self {
+                MyState::Start => Some(Self::Middle),
+                MyState::Middle => Some(Self::End),
+                MyState::End => None,
+            };
+
+            context.items.push(context.items.len() as u8);
+
+            Ok(next_state)
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let (progress, snapshots): (Vec<_>, Vec<_>) = Streamline::build(MyState::Start)
+            .context(Context { items: vec![] })
+            .run_with_snapshots()
+            .unzip()
+            .await;
+
+        // the context that the state at index 1 started from is the one left by index 0
+        let streamline = Streamline::replay_from(&progress, 1)
+            .expect("could not replay")
+            .context(snapshots[0].clone().expect("missing context"));
+
+        let checkpoint = serde_json::to_string(&streamline).expect("could not serialize");
+
+        let restored: Streamline<Context, (), MyState> =
+            serde_json::from_str(&checkpoint).expect("could not deserialize");
+
+        let (rest, rest_snapshots): (Vec<_>, Vec<_>) = restored.run_with_snapshots().unzip().await;
+
+        assert_eq!(rest, progress[1..]);
+        assert_eq!(rest_snapshots, snapshots[1..]);
+    });
+}
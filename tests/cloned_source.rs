@@ -0,0 +1,42 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use streamline::{Progress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn clones_source_out_of_arc() {
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        End,
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct MyError(&'static str);
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = ();
+        type Error = MyError;
+
+        async fn next(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::Start => Ok(Some(Self::End)),
+                MyState::End => Err(MyError("Something went wrong!")),
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        match Streamline::build(MyState::Start).last().await {
+            Some(Progress::Revert(revert_progress)) => assert_eq!(
+                revert_progress.cloned_source(),
+                Some(MyError("Something went wrong!"))
+            ),
+            _ => panic!("incorrect terminal state found"),
+        }
+    });
+}
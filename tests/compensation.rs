@@ -0,0 +1,104 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use futures::{FutureExt, StreamExt};
+use streamline::{CompensationRegistry, Progress, RevertProgress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn runs_registered_compensations() {
+    #[derive(Debug, PartialEq)]
+    struct Context {
+        compensated: Vec<&'static str>,
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        Middle,
+        End,
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct MyError(&'static str);
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = Context;
+        type Error = MyError;
+
+        async fn next(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::Start => Ok(Some(Self::Middle)),
+                MyState::Middle => Ok(Some(Self::End)),
+                MyState::End => Err(MyError("Something went wrong!")),
+            }
+        }
+
+        async fn revert(
+            &self,
+            context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::End => {
+                    context.unwrap().compensated.push("revert End");
+
+                    Ok(Some(Self::Middle))
+                }
+                _ => Ok(None),
+            }
+        }
+    }
+
+    let registry = CompensationRegistry::<_, Context, _>::new()
+        .register(MyState::End, |_, context| {
+            async move {
+                context.unwrap().compensated.push("registry End");
+
+                Ok(None)
+            }
+            .boxed_local()
+        })
+        .register(MyState::Middle, |_, context| {
+            async move {
+                context.unwrap().compensated.push("registry Middle");
+
+                Ok(Some(MyState::Start))
+            }
+            .boxed_local()
+        });
+
+    Runtime::new().unwrap().block_on(async {
+        let states: Vec<_> = Streamline::build(MyState::Start)
+            .context(Context {
+                compensated: vec![],
+            })
+            .with_compensations(registry)
+            .run()
+            .collect()
+            .await;
+
+        let reverted: Vec<_> = states
+            .iter()
+            .filter_map(|progress| match progress {
+                Progress::Revert(RevertProgress::Reverting { step, .. }) => Some(step),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(reverted, [&MyState::End, &MyState::Middle, &MyState::Start]);
+
+        match states.last() {
+            Some(Progress::Revert(RevertProgress::Reverted {
+                source: Some(source),
+                compensated_states,
+            })) => {
+                assert_eq!(**source, MyError("Something went wrong!"));
+                assert_eq!(compensated_states, &[MyState::End, MyState::Middle]);
+            }
+            _ => panic!("incorrect terminal state found"),
+        }
+    });
+}
@@ -0,0 +1,74 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use futures::StreamExt;
+use streamline::{Progress, RevertProgress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn emits_completion_marker() {
+    #[derive(Debug, PartialEq)]
+    struct Context;
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = Context;
+        type Error = ();
+
+        async fn next(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::Start => Ok(Some(Self::End)),
+                MyState::End => Ok(None),
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let completed: Vec<_> = Streamline::build(MyState::Start)
+            .context(Context)
+            .emit_completion_marker()
+            .run()
+            .collect()
+            .await;
+
+        assert_eq!(
+            completed,
+            [
+                Progress::Ok(MyState::Start),
+                Progress::Ok(MyState::End),
+                Progress::Done,
+            ]
+        );
+
+        let reverted: Vec<_> = Streamline::build(MyState::Start)
+            .context(Context)
+            .emit_completion_marker()
+            .run_with_deadline(std::time::Instant::now())
+            .collect()
+            .await;
+
+        assert_eq!(
+            reverted,
+            [
+                Progress::Ok(MyState::Start),
+                Progress::Revert(RevertProgress::Reverting {
+                    step: MyState::Start,
+                    source: None
+                }),
+                Progress::Revert(RevertProgress::Reverted {
+                    source: None,
+                    compensated_states: vec![],
+                }),
+                Progress::Done,
+            ]
+        );
+    });
+}
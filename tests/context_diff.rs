@@ -0,0 +1,61 @@
+use async_trait::async_trait;
+use futures::StreamExt;
+use streamline::{Progress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn reports_context_changes() {
+    #[derive(Clone, Debug, PartialEq)]
+    struct Context {
+        items: Vec<u8>,
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        Middle,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = Context;
+        type Error = ();
+
+        async fn next(
+            &self,
+            context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            let context = context.ok_or(())?;
+
+            let next_state = match self {
+                MyState::Start => Some(Self::Middle),
+                MyState::Middle => {
+                    context.items.push(1);
+
+                    Some(Self::End)
+                }
+                MyState::End => None,
+            };
+
+            Ok(next_state)
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let diffs: Vec<_> = Streamline::build(MyState::Start)
+            .context(Context { items: vec![] })
+            .run_with_context_diff()
+            .collect()
+            .await;
+
+        assert_eq!(
+            diffs,
+            [
+                (Progress::Ok(MyState::Start), false),
+                (Progress::Ok(MyState::Middle), true),
+                (Progress::Ok(MyState::End), false),
+            ]
+        );
+    });
+}
@@ -0,0 +1,95 @@
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::collections::HashMap;
+use streamline::{ContextIdempotency, Progress, SharedContext, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[derive(Debug, Default, PartialEq)]
+struct Context {
+    charges: u32,
+    completed: HashMap<String, Option<MyState>>,
+}
+
+impl ContextIdempotency<MyState> for Context {
+    fn has_run(&self, key: &str) -> Option<Option<MyState>> {
+        self.completed.get(key).cloned()
+    }
+
+    fn mark_run(&mut self, key: String, next: Option<MyState>) {
+        self.completed.insert(key, next);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum MyState {
+    Start,
+    Charge,
+    Ship,
+}
+
+#[async_trait(?Send)]
+impl State for MyState {
+    type Context = Context;
+    type Error = ();
+
+    async fn next(&self, context: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+        let next_state = match self {
+            MyState::Start => Some(Self::Charge),
+            MyState::Charge => {
+                context.unwrap().charges += 1;
+
+                Some(Self::Ship)
+            }
+            MyState::Ship => None,
+        };
+
+        Ok(next_state)
+    }
+
+    fn idempotency_key(&self) -> Option<String> {
+        match self {
+            MyState::Charge => Some("charge".into()),
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn skips_states_recorded_in_the_context_on_resume() {
+    Runtime::new().unwrap().block_on(async {
+        let shared = SharedContext::new(Context::default());
+
+        // stop the first run right after the charge went through
+        let interrupted: Vec<_> = Streamline::build(MyState::Start)
+            .share_context(shared.clone())
+            .idempotent_in_context()
+            .run()
+            .take(2)
+            .collect()
+            .await;
+
+        assert_eq!(
+            interrupted,
+            [Progress::Ok(MyState::Start), Progress::Ok(MyState::Charge)]
+        );
+
+        // then resume from the start with the same context
+        let resumed: Vec<_> = Streamline::build(MyState::Start)
+            .share_context(shared.clone())
+            .idempotent_in_context()
+            .run()
+            .collect()
+            .await;
+
+        assert_eq!(
+            resumed,
+            [
+                Progress::Ok(MyState::Start),
+                Progress::Ok(MyState::Charge),
+                Progress::Ok(MyState::Ship),
+            ]
+        );
+
+        assert_eq!(shared.with(|context| context.charges).await, 1);
+    });
+}
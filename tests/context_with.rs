@@ -0,0 +1,50 @@
+use async_trait::async_trait;
+use futures::StreamExt;
+use streamline::{Progress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn layers_context() {
+    #[derive(Debug, Default, PartialEq)]
+    struct Config {
+        layers: Vec<&'static str>,
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Report(Vec<&'static str>);
+
+    #[async_trait(?Send)]
+    impl State for Report {
+        type Context = Config;
+        type Error = ();
+
+        async fn next(
+            &self,
+            context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            match context {
+                Some(config) if self.0.is_empty() => Ok(Some(Report(config.layers.clone()))),
+                _ => Ok(None),
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let states: Vec<_> = Streamline::build(Report(vec![]))
+            .context_with(|config| config.layers.push("ignored"))
+            .context(Config::default())
+            .context_with(|config| config.layers.push("env"))
+            .context_with(|config| config.layers.push("flags"))
+            .run()
+            .collect()
+            .await;
+
+        assert_eq!(
+            states,
+            [
+                Progress::Ok(Report(vec![])),
+                Progress::Ok(Report(vec!["env", "flags"])),
+            ]
+        );
+    });
+}
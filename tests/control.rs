@@ -0,0 +1,65 @@
+use async_trait::async_trait;
+use futures::StreamExt;
+use streamline::{Progress, SharedContext, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[derive(Clone, Debug, PartialEq)]
+enum MyState {
+    Start,
+    Middle,
+    Detour,
+    End,
+}
+
+#[async_trait(?Send)]
+impl State for MyState {
+    type Context = Vec<MyState>;
+    type Error = ();
+
+    async fn next(&self, context: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+        context.unwrap().push(self.clone());
+
+        let next_state = match self {
+            MyState::Start => Some(Self::Middle),
+            MyState::Middle | MyState::Detour => Some(Self::End),
+            MyState::End => None,
+        };
+
+        Ok(next_state)
+    }
+}
+
+#[test]
+fn forces_next_states() {
+    Runtime::new().unwrap().block_on(async {
+        let ran = SharedContext::new(vec![]);
+
+        let (stream, control) = Streamline::build(MyState::Start)
+            .share_context(ran.clone())
+            .run_controlled();
+
+        let mut stream = stream.boxed_local();
+
+        let mut states = vec![stream.next().await.unwrap()];
+
+        control.force_next(MyState::Detour);
+
+        states.extend(stream.collect::<Vec<_>>().await);
+
+        assert_eq!(
+            states,
+            [
+                Progress::Ok(MyState::Start),
+                Progress::Ok(MyState::Middle),
+                Progress::Ok(MyState::Detour),
+                Progress::Ok(MyState::End),
+            ]
+        );
+
+        // the forced transition skipped Middle's own next entirely
+        assert_eq!(
+            ran.try_unwrap().unwrap(),
+            [MyState::Start, MyState::Detour, MyState::End]
+        );
+    });
+}
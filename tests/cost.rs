@@ -0,0 +1,58 @@
+use async_trait::async_trait;
+use streamline::{State, Streamline};
+
+#[test]
+fn estimates_remaining_cost() {
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        Middle,
+        End,
+        Retry,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = ();
+        type Error = ();
+
+        async fn next(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            Ok(None)
+        }
+
+        fn transitions(&self) -> Vec<Self> {
+            match self {
+                MyState::Start => vec![Self::Middle],
+                MyState::Middle => vec![Self::End, Self::Retry],
+                MyState::End => vec![],
+                MyState::Retry => vec![Self::Retry],
+            }
+        }
+
+        fn cost(&self) -> u64 {
+            match self {
+                MyState::Start => 5,
+                MyState::Middle => 2,
+                _ => 1,
+            }
+        }
+    }
+
+    assert_eq!(
+        Streamline::build(MyState::Start).estimated_remaining_cost(),
+        Some(8)
+    );
+
+    assert_eq!(
+        Streamline::build(MyState::Middle).estimated_remaining_cost(),
+        Some(3)
+    );
+
+    assert_eq!(
+        Streamline::build(MyState::Retry).estimated_remaining_cost(),
+        None
+    );
+}
@@ -0,0 +1,39 @@
+use async_trait::async_trait;
+use streamline::{count_states, State};
+
+#[derive(Clone, Debug, PartialEq)]
+enum MyState {
+    Start,
+    Poll,
+    Wait,
+    Done,
+}
+
+#[async_trait(?Send)]
+impl State for MyState {
+    type Context = ();
+    type Error = ();
+
+    async fn next(
+        &self,
+        _context: Option<&mut Self::Context>,
+    ) -> Result<Option<Self>, Self::Error> {
+        Ok(self.transitions().into_iter().next())
+    }
+
+    fn transitions(&self) -> Vec<Self> {
+        match self {
+            MyState::Start => vec![MyState::Poll],
+            MyState::Poll => vec![MyState::Wait, MyState::Done],
+            MyState::Wait => vec![MyState::Poll],
+            MyState::Done => vec![],
+        }
+    }
+}
+
+#[test]
+fn counts_distinct_reachable_states() {
+    assert_eq!(count_states(MyState::Start), 4);
+    assert_eq!(count_states(MyState::Wait), 3);
+    assert_eq!(count_states(MyState::Done), 1);
+}
@@ -0,0 +1,83 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::time::{Duration, Instant};
+use streamline::{Progress, RevertProgress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn reverts_after_deadline() {
+    #[derive(Debug, PartialEq)]
+    struct Context;
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        Middle,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = Context;
+        type Error = ();
+
+        async fn next(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            let next_state = match self {
+                MyState::Start => {
+                    std::thread::sleep(Duration::from_millis(20));
+
+                    Some(Self::Middle)
+                }
+                MyState::Middle => Some(Self::End),
+                MyState::End => None,
+            };
+
+            Ok(next_state)
+        }
+
+        async fn revert(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            let next_state = match self {
+                MyState::End => Some(Self::Middle),
+                MyState::Middle => Some(Self::Start),
+                MyState::Start => None,
+            };
+
+            Ok(next_state)
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let states: Vec<_> = Streamline::build(MyState::Start)
+            .context(Context)
+            .run_with_deadline(Instant::now() + Duration::from_millis(10))
+            .collect()
+            .await;
+
+        assert_eq!(
+            states,
+            [
+                Progress::Ok(MyState::Start),
+                Progress::Ok(MyState::Middle),
+                Progress::Revert(RevertProgress::Reverting {
+                    step: MyState::Middle,
+                    source: None
+                }),
+                Progress::Revert(RevertProgress::Reverting {
+                    step: MyState::Start,
+                    source: None
+                }),
+                Progress::Revert(RevertProgress::Reverted {
+                    source: None,
+                    compensated_states: vec![MyState::Middle],
+                }),
+            ]
+        );
+    });
+}
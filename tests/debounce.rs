@@ -0,0 +1,79 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::{thread, time::Duration};
+use streamline::{DebouncedCancel, Progress, RevertProgress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[derive(Debug)]
+struct Context {
+    cancel: Option<DebouncedCancel>,
+    withdraw: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum MyState {
+    Start,
+    Middle,
+    End,
+}
+
+#[async_trait(?Send)]
+impl State for MyState {
+    type Context = Context;
+    type Error = ();
+
+    async fn next(&self, context: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+        let context = context.ok_or(())?;
+
+        match self {
+            MyState::Start => {
+                let cancel = context.cancel.take().ok_or(())?;
+
+                cancel.cancel();
+
+                // withdraw the cancellation while the Streamline is waiting on it
+                if context.withdraw {
+                    thread::spawn(move || {
+                        thread::sleep(Duration::from_millis(20));
+                        cancel.uncancel();
+                    });
+                }
+
+                Ok(Some(Self::Middle))
+            }
+            MyState::Middle => Ok(Some(Self::End)),
+            MyState::End => Ok(None),
+        }
+    }
+}
+
+async fn run(withdraw: bool) -> Option<Progress<MyState, (), Context>> {
+    let (streamline, cancel) =
+        Streamline::build(MyState::Start).cancel_debounced(Duration::from_millis(200));
+
+    streamline
+        .context(Context {
+            cancel: Some(cancel),
+            withdraw,
+        })
+        .run()
+        .collect::<Vec<_>>()
+        .await
+        .pop()
+}
+
+#[test]
+fn debounces_cancellation() {
+    Runtime::new().unwrap().block_on(async {
+        assert!(matches!(run(true).await, Some(Progress::Ok(MyState::End))));
+
+        assert!(matches!(
+            run(false).await,
+            Some(Progress::Revert(RevertProgress::Reverted {
+                source: None,
+                ..
+            }))
+        ));
+    });
+}
@@ -0,0 +1,42 @@
+use async_trait::async_trait;
+use streamline::{State, Streamline};
+use tokio::runtime::Runtime;
+
+#[derive(Clone, Debug, PartialEq)]
+enum MyState {
+    Start,
+    End,
+}
+
+#[async_trait(?Send)]
+impl State for MyState {
+    type Context = ();
+    type Error = ();
+
+    async fn next(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+        match self {
+            MyState::Start => Ok(Some(MyState::End)),
+            MyState::End => Ok(None),
+        }
+    }
+}
+
+#[test]
+fn accepts_progressing_start_states() {
+    Runtime::new().unwrap().block_on(async {
+        Streamline::build(MyState::Start)
+            .debug_assert_progresses()
+            .await;
+    });
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "starting from End is immediately terminal")]
+fn rejects_terminal_start_states() {
+    Runtime::new().unwrap().block_on(async {
+        Streamline::build(MyState::End)
+            .debug_assert_progresses()
+            .await;
+    });
+}
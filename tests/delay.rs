@@ -0,0 +1,89 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::time::{Duration, Instant};
+use streamline::{Progress, RevertProgress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[derive(Clone, Debug, PartialEq)]
+enum MyState {
+    Start,
+    Scheduled(Instant),
+    End,
+}
+
+#[async_trait(?Send)]
+impl State for MyState {
+    type Context = ();
+    type Error = ();
+
+    async fn next(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+        match self {
+            MyState::Start => Ok(Some(MyState::Scheduled(
+                Instant::now() + Duration::from_millis(50),
+            ))),
+            MyState::Scheduled(_) => Ok(Some(MyState::End)),
+            MyState::End => Ok(None),
+        }
+    }
+
+    async fn revert(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+        Ok(None)
+    }
+
+    fn delay_until(&self) -> Option<Instant> {
+        match self {
+            MyState::Scheduled(until) => Some(*until),
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn delays_transitions() {
+    Runtime::new().unwrap().block_on(async {
+        let started = Instant::now();
+
+        let last = Streamline::build(MyState::Start).last().await;
+
+        assert_eq!(last, Some(Progress::Ok(MyState::End)));
+        assert!(started.elapsed() >= Duration::from_millis(50));
+    });
+}
+
+#[test]
+fn cancels_during_delay() {
+    Runtime::new().unwrap().block_on(async {
+        let started = Instant::now();
+        let until = started + Duration::from_secs(60);
+
+        let (stream, cancel) = Streamline::build(MyState::Scheduled(until)).run_preemptible();
+
+        let (_, states) = futures::join!(
+            async move {
+                futures_timer::Delay::new(Duration::from_millis(20)).await;
+                cancel.cancel().unwrap();
+            },
+            stream.collect::<Vec<_>>()
+        );
+
+        assert_eq!(
+            states,
+            [
+                Progress::Ok(MyState::Scheduled(until)),
+                Progress::CancellationRequested {
+                    step: MyState::Scheduled(until)
+                },
+                Progress::Revert(RevertProgress::Reverting {
+                    step: MyState::Scheduled(until),
+                    source: None,
+                }),
+                Progress::Revert(RevertProgress::Reverted {
+                    source: None,
+                    compensated_states: vec![],
+                }),
+            ]
+        );
+        assert!(started.elapsed() < Duration::from_secs(60));
+    });
+}
@@ -0,0 +1,86 @@
+#![cfg(all(feature = "serde", feature = "revert"))]
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use streamline::{Progress, RevertProgress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn restores_cancellation_after_checkpoints() {
+    #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+    enum MyState {
+        Start,
+        Middle,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = ();
+        type Error = ();
+
+        async fn next(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+            let next_state = match self {
+                MyState::Start => Some(Self::Middle),
+                MyState::Middle => Some(Self::End),
+                MyState::End => None,
+            };
+
+            Ok(next_state)
+        }
+
+        async fn revert(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+            let next_state = match self {
+                MyState::End => Some(Self::Middle),
+                MyState::Middle => Some(Self::Start),
+                MyState::Start => None,
+            };
+
+            Ok(next_state)
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let (mut streamline, cancel) = Streamline::build(MyState::Middle).preemptible();
+
+        let receiver = streamline
+            .detach_cancel()
+            .expect("missing cancellation receiver");
+
+        let checkpoint = serde_json::to_string(&streamline).expect("could not serialize");
+
+        // requests made while the receiver is detached are held back rather than lost
+        cancel.cancel().unwrap();
+
+        let mut restored: Streamline<(), (), MyState> =
+            serde_json::from_str(&checkpoint).expect("could not deserialize");
+
+        assert!(restored.detach_cancel().is_none());
+
+        restored.attach_cancel(receiver);
+
+        let states: Vec<_> = restored.run().collect().await;
+
+        assert_eq!(
+            states,
+            [
+                Progress::Ok(MyState::Middle),
+                Progress::CancellationRequested {
+                    step: MyState::Middle
+                },
+                Progress::Revert(RevertProgress::Reverting {
+                    step: MyState::Middle,
+                    source: None,
+                }),
+                Progress::Revert(RevertProgress::Reverting {
+                    step: MyState::Start,
+                    source: None,
+                }),
+                Progress::Revert(RevertProgress::Reverted {
+                    source: None,
+                    compensated_states: vec![MyState::Middle],
+                }),
+            ]
+        );
+    });
+}
@@ -1,3 +1,4 @@
+#![cfg(feature = "revert")]
 use async_trait::async_trait;
 use futures::StreamExt;
 use streamline::{Progress, RevertProgress, State, Streamline};
@@ -65,6 +66,7 @@ fn completes_down() {
         match states.last() {
             Some(Progress::Revert(RevertProgress::Reverted {
                 source: Some(source),
+                ..
             })) => assert_eq!(**source, MyError("Something went wrong!")),
             _ => panic!("incorrect terminal state found"),
         }
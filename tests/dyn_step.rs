@@ -0,0 +1,93 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use futures::StreamExt;
+use streamline::{DynStep, DynSteps, Progress, RevertProgress, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn runs_dynamic_steps() {
+    #[derive(Clone, Debug, PartialEq)]
+    struct Context {
+        log: Vec<String>,
+    }
+
+    struct Plugin {
+        name: &'static str,
+        fail: bool,
+    }
+
+    #[async_trait(?Send)]
+    impl DynStep<Context, &'static str> for Plugin {
+        async fn run(&self, context: Option<&mut Context>) -> Result<(), &'static str> {
+            if self.fail {
+                return Err("Plugin failed!");
+            }
+
+            context
+                .ok_or("Missing context")?
+                .log
+                .push(format!("run {}", self.name));
+
+            Ok(())
+        }
+
+        async fn undo(&self, context: Option<&mut Context>) -> Result<(), &'static str> {
+            context
+                .ok_or("Missing context")?
+                .log
+                .push(format!("undo {}", self.name));
+
+            Ok(())
+        }
+    }
+
+    let plugins = |fail: bool| -> Vec<Box<dyn DynStep<Context, &'static str>>> {
+        vec![
+            Box::new(Plugin {
+                name: "a",
+                fail: false,
+            }),
+            Box::new(Plugin {
+                name: "b",
+                fail: false,
+            }),
+            Box::new(Plugin { name: "c", fail }),
+        ]
+    };
+
+    Runtime::new().unwrap().block_on(async {
+        let completed: Vec<_> = Streamline::build(DynSteps::new(plugins(false)))
+            .context(Context { log: vec![] })
+            .run_with_snapshots()
+            .collect()
+            .await;
+
+        let positions: Vec<_> = completed
+            .iter()
+            .filter_map(|(progress, _)| match progress {
+                Progress::Ok(state) => Some(state.position()),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(positions, [0, 1, 2, 3]);
+
+        match completed.last() {
+            Some((_, Some(context))) => assert_eq!(context.log, ["run a", "run b", "run c"]),
+            _ => panic!("missing context"),
+        }
+
+        let reverted: Vec<_> = Streamline::build(DynSteps::new(plugins(true)))
+            .context(Context { log: vec![] })
+            .run_with_snapshots()
+            .collect()
+            .await;
+
+        match reverted.last() {
+            Some((Progress::Revert(RevertProgress::Reverted { .. }), Some(context))) => {
+                assert_eq!(context.log, ["run a", "run b", "undo b", "undo a"])
+            }
+            _ => panic!("incorrect terminal state found"),
+        }
+    });
+}
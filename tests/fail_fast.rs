@@ -0,0 +1,86 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::collections::HashMap;
+use streamline::{Progress, RevertProgress, State, Streamline, Supervisor};
+use tokio::runtime::Runtime;
+
+#[test]
+fn cancels_siblings_on_reversion() {
+    #[derive(Clone, Debug, PartialEq)]
+    struct MyState {
+        count: u32,
+        fail_at: u32,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = ();
+        type Error = &'static str;
+
+        async fn next(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            if self.count == self.fail_at {
+                return Err("Something went wrong!");
+            }
+
+            Ok(Some(Self {
+                count: self.count + 1,
+                fail_at: self.fail_at,
+            }))
+        }
+
+        async fn revert(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            match self.count {
+                0 => Ok(None),
+                count => Ok(Some(Self {
+                    count: count - 1,
+                    fail_at: self.fail_at,
+                })),
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let mut supervisor = Supervisor::new();
+
+        let failing = supervisor.add(Streamline::build(MyState {
+            count: 0,
+            fail_at: 2,
+        }));
+
+        for _ in 0..2 {
+            supervisor.add(Streamline::build(MyState {
+                count: 0,
+                fail_at: 100,
+            }));
+        }
+
+        let mut last_progress = HashMap::new();
+
+        supervisor
+            .run_all_or_cancel()
+            .for_each(|(id, progress)| {
+                last_progress.insert(id, progress);
+
+                async {}
+            })
+            .await;
+
+        assert_eq!(last_progress.len(), 3);
+
+        for (id, progress) in last_progress {
+            match progress {
+                Progress::Revert(RevertProgress::Reverted { source, .. }) => {
+                    assert_eq!(source.is_some(), id == failing)
+                }
+                _ => panic!("incorrect terminal state found"),
+            }
+        }
+    });
+}
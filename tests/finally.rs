@@ -0,0 +1,128 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::{cell::RefCell, rc::Rc};
+use streamline::{Progress, State, Streamline, TerminalOutcome};
+use tokio::runtime::Runtime;
+
+#[test]
+fn runs_cleanup_once() {
+    #[derive(Debug, PartialEq)]
+    struct Context {
+        connection: &'static str,
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        Middle,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = Context;
+        type Error = ();
+
+        async fn next(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::Start => Ok(Some(Self::Middle)),
+                MyState::Middle => Ok(Some(Self::End)),
+                MyState::End => Ok(None),
+            }
+        }
+    }
+
+    let closed = Rc::new(RefCell::new(vec![]));
+
+    let streamline = |closed: Rc<RefCell<Vec<(TerminalOutcome, &'static str)>>>| {
+        Streamline::build(MyState::Start)
+            .context(Context { connection: "db" })
+            .finally(move |outcome, context| {
+                if let Some(context) = context {
+                    closed.borrow_mut().push((outcome, context.connection));
+                }
+            })
+    };
+
+    Runtime::new().unwrap().block_on(async {
+        let mut stream = Box::pin(streamline(closed.clone()).run());
+
+        while let Some(progress) = stream.next().await {
+            // cleanup runs right before the final state is emitted
+            let expected = if progress == Progress::Ok(MyState::End) {
+                1
+            } else {
+                0
+            };
+
+            assert_eq!(closed.borrow().len(), expected);
+        }
+
+        drop(stream);
+
+        assert_eq!(*closed.borrow(), [(TerminalOutcome::Completed, "db")]);
+
+        let mut stream = Box::pin(streamline(closed.clone()).run());
+
+        stream.next().await;
+
+        assert_eq!(*closed.borrow(), [(TerminalOutcome::Completed, "db")]);
+
+        drop(stream);
+
+        assert_eq!(
+            *closed.borrow(),
+            [
+                (TerminalOutcome::Completed, "db"),
+                (TerminalOutcome::Cancelled, "db")
+            ]
+        );
+    });
+}
+
+#[test]
+fn reports_how_the_streamline_ended() {
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = ();
+        type Error = ();
+
+        async fn next(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::Start => Ok(Some(Self::End)),
+                MyState::End => Err(()),
+            }
+        }
+
+        async fn revert(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::End => Ok(Some(Self::Start)),
+                MyState::Start => Err(()),
+            }
+        }
+    }
+
+    let outcome = Rc::new(RefCell::new(None));
+
+    Runtime::new().unwrap().block_on(async {
+        let recorded = outcome.clone();
+
+        Streamline::build(MyState::Start)
+            .finally(move |outcome, _| *recorded.borrow_mut() = Some(outcome))
+            .run()
+            .for_each(|_| async {})
+            .await;
+
+        assert_eq!(*outcome.borrow(), Some(TerminalOutcome::Failed));
+    });
+}
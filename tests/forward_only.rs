@@ -0,0 +1,75 @@
+#![cfg(not(feature = "revert"))]
+use async_trait::async_trait;
+use futures::StreamExt;
+use streamline::{Progress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn stops_on_error_without_reverting() {
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        Middle,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = ();
+        type Error = &'static str;
+
+        async fn next(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::Start => Ok(Some(Self::Middle)),
+                MyState::Middle => Err("Something went wrong!"),
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let progress: Vec<_> = Streamline::build(MyState::Start).run().collect().await;
+
+        assert_eq!(
+            progress,
+            [
+                Progress::Ok(MyState::Start),
+                Progress::Ok(MyState::Middle),
+                Progress::Failed {
+                    source: Some("Something went wrong!")
+                },
+            ]
+        );
+    });
+}
+
+#[test]
+fn ends_with_failure() {
+    #[derive(Clone, Debug, PartialEq)]
+    struct MyState;
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = ();
+        type Error = &'static str;
+
+        async fn next(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            Err("Something went wrong!")
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let last = Streamline::build(MyState).last().await;
+
+        assert_eq!(
+            last,
+            Some(Progress::Failed {
+                source: Some("Something went wrong!")
+            })
+        );
+    });
+}
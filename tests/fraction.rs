@@ -0,0 +1,133 @@
+use async_trait::async_trait;
+use futures::StreamExt;
+use streamline::{State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn reports_forward_and_revert_fractions() {
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        Middle,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = ();
+        type Error = ();
+
+        async fn next(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::Start => Ok(Some(MyState::Middle)),
+                MyState::Middle => Ok(Some(MyState::End)),
+                MyState::End => Err(()),
+            }
+        }
+
+        #[cfg(feature = "revert")]
+        async fn revert(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::End => Ok(Some(MyState::Middle)),
+                MyState::Middle => Ok(Some(MyState::Start)),
+                MyState::Start => Ok(None),
+            }
+        }
+
+        fn transitions(&self) -> Vec<Self> {
+            match self {
+                MyState::Start => vec![MyState::Middle],
+                MyState::Middle => vec![MyState::End],
+                MyState::End => vec![],
+            }
+        }
+
+        #[cfg(feature = "revert")]
+        fn revert_transitions(&self) -> Vec<Self> {
+            match self {
+                MyState::Start => vec![],
+                MyState::Middle => vec![MyState::Start],
+                MyState::End => vec![MyState::Middle],
+            }
+        }
+
+        fn cost(&self) -> u64 {
+            match self {
+                MyState::Start => 1,
+                MyState::Middle => 3,
+                MyState::End => 4,
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let fractions: Vec<_> = Streamline::build(MyState::Start)
+            .run_with_progress()
+            .map(|(_, fraction)| fraction)
+            .collect()
+            .await;
+
+        #[cfg(feature = "revert")]
+        assert_eq!(
+            fractions,
+            [
+                Some(0.0),
+                Some(0.125),
+                Some(0.5),
+                Some(0.0),
+                Some(1.0 / 3.0),
+                Some(2.0 / 3.0),
+                Some(1.0),
+            ]
+        );
+
+        #[cfg(not(feature = "revert"))]
+        assert_eq!(fractions, [Some(0.0), Some(0.125), Some(0.5), None]);
+    });
+}
+
+#[test]
+fn handles_free_states() {
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        Middle,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = ();
+        type Error = ();
+
+        async fn next(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::Start => Ok(Some(MyState::Middle)),
+                MyState::Middle => Ok(Some(MyState::End)),
+                MyState::End => Ok(None),
+            }
+        }
+
+        fn transitions(&self) -> Vec<Self> {
+            match self {
+                MyState::Start => vec![MyState::Middle],
+                MyState::Middle => vec![MyState::End],
+                MyState::End => vec![],
+            }
+        }
+
+        fn cost(&self) -> u64 {
+            0
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let fractions: Vec<_> = Streamline::build(MyState::Start)
+            .run_with_progress()
+            .map(|(_, fraction)| fraction)
+            .collect()
+            .await;
+
+        assert_eq!(fractions, [Some(0.0), Some(0.0), Some(1.0)]);
+    });
+}
@@ -0,0 +1,108 @@
+#![cfg(all(feature = "serde", feature = "revert"))]
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use streamline::{decode_frames, Progress, RevertProgress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn decodes_frames() {
+    #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+    enum MyState {
+        Start,
+        Middle(String),
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = ();
+        type Error = String;
+
+        async fn next(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::Start => Ok(Some(Self::Middle("hooray!".into()))),
+                MyState::Middle(_) => Err("oops".into()),
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let frames: Vec<_> = Streamline::build(MyState::Start)
+            .emit_start_marker()
+            .run_framed()
+            .map(|frame| frame.expect("could not encode"))
+            .collect()
+            .await;
+
+        assert_eq!(&frames[0][..4], &[0, 0, 0, 14]);
+        assert_eq!(&frames[0][4..], br#"{"Ok":"Start"}"#);
+
+        // feed the frames through in uneven chunks
+        let bytes = frames.concat();
+        let mut buffer = vec![];
+        let mut states: Vec<Progress<MyState, String, ()>> = vec![];
+
+        for chunk in bytes.chunks(7) {
+            buffer.extend_from_slice(chunk);
+            states.extend(
+                decode_frames(&mut buffer)
+                    .into_iter()
+                    .map(|state| state.expect("could not decode")),
+            );
+        }
+
+        assert!(buffer.is_empty());
+
+        assert_eq!(
+            states,
+            [
+                Progress::Ok(MyState::Start),
+                Progress::Ok(MyState::Middle("hooray!".into())),
+                Progress::Revert(RevertProgress::Reverting {
+                    step: MyState::Middle("hooray!".into()),
+                    source: Some("oops".to_string().into()),
+                }),
+                Progress::Revert(RevertProgress::Reverted {
+                    source: Some("oops".to_string().into()),
+                    compensated_states: vec![],
+                }),
+            ]
+        );
+    });
+}
+
+#[test]
+fn skips_bad_frames() {
+    #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+    enum MyState {
+        Start,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = ();
+        type Error = String;
+
+        async fn next(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+            Ok(None)
+        }
+    }
+
+    let payload = br#"{"Ok":"Start"}"#;
+    let garbage = b"not json";
+    let mut buffer = vec![];
+
+    for frame in [&payload[..], &garbage[..], &payload[..]] {
+        buffer.extend_from_slice(&(frame.len() as u32).to_be_bytes());
+        buffer.extend_from_slice(frame);
+    }
+
+    let states: Vec<Result<Progress<MyState, String, ()>, _>> = decode_frames(&mut buffer);
+
+    assert!(buffer.is_empty());
+
+    match states.as_slice() {
+        [Ok(Progress::Ok(MyState::Start)), Err(_), Ok(Progress::Ok(MyState::Start))] => {}
+        _ => panic!("incorrect frames found"),
+    }
+}
@@ -0,0 +1,51 @@
+use async_trait::async_trait;
+use futures::{stream::FusedStream, StreamExt};
+use streamline::{Progress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn stays_terminated() {
+    struct Context;
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = Context;
+        type Error = ();
+
+        async fn next(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            let next_state = match self {
+                MyState::Start => Some(Self::End),
+                _ => None,
+            };
+
+            Ok(next_state)
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let mut stream = Box::pin(Streamline::build(MyState::Start).context(Context).run());
+
+        assert!(matches!(
+            stream.next().await,
+            Some(Progress::Ok(MyState::Start))
+        ));
+        assert!(matches!(
+            stream.next().await,
+            Some(Progress::Ok(MyState::End))
+        ));
+        assert!(!stream.is_terminated());
+        assert!(stream.next().await.is_none());
+        assert!(stream.is_terminated());
+        assert!(stream.next().await.is_none());
+        assert!(stream.next().await.is_none());
+    });
+}
@@ -0,0 +1,52 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use streamline::{validate_graph, GraphError, State};
+
+#[test]
+fn finds_missing_revert_transitions() {
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        Middle,
+        Retry,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = ();
+        type Error = ();
+
+        async fn next(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+            Ok(None)
+        }
+
+        fn transitions(&self) -> Vec<Self> {
+            match self {
+                MyState::Start => vec![MyState::Middle],
+                MyState::Middle => vec![MyState::End, MyState::Retry],
+                MyState::Retry => vec![MyState::Middle],
+                MyState::End => vec![],
+            }
+        }
+
+        fn revert_transitions(&self) -> Vec<Self> {
+            match self {
+                MyState::Start => vec![],
+                MyState::Middle => vec![MyState::Start, MyState::Retry],
+                MyState::Retry => vec![],
+                MyState::End => vec![MyState::Middle],
+            }
+        }
+    }
+
+    assert_eq!(validate_graph(MyState::End), Ok(()));
+
+    assert_eq!(
+        validate_graph(MyState::Start),
+        Err(GraphError {
+            from: MyState::Middle,
+            to: MyState::Retry,
+        })
+    );
+}
@@ -0,0 +1,57 @@
+use async_trait::async_trait;
+use futures::StreamExt;
+use streamline::{Progress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn passes_visited_states() {
+    #[derive(Clone, Debug, PartialEq)]
+    struct Node(u8);
+
+    #[async_trait(?Send)]
+    impl State for Node {
+        type Context = ();
+        type Error = ();
+
+        async fn next(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+            unreachable!("Streamlines should call next_with_history")
+        }
+
+        async fn next_with_history(
+            &self,
+            history: &[Self],
+            _: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            // visit the first neighbour that hasn't been visited yet
+            let next = [(self.0 + 1) % 4, (self.0 + 3) % 4]
+                .iter()
+                .map(|&neighbour| Node(neighbour))
+                .find(|neighbour| !history.contains(neighbour) && neighbour != self);
+
+            Ok(next)
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let states: Vec<_> = Streamline::build(Node(0))
+            .track_history()
+            .run()
+            .collect()
+            .await;
+
+        assert_eq!(
+            states,
+            [
+                Progress::Ok(Node(0)),
+                Progress::Ok(Node(1)),
+                Progress::Ok(Node(2)),
+                Progress::Ok(Node(3)),
+            ]
+        );
+
+        // without tracking, only the previous state is known
+        let states: Vec<_> = Streamline::build(Node(0)).run().take(6).collect().await;
+
+        assert_eq!(states.len(), 6);
+    });
+}
@@ -0,0 +1,67 @@
+use async_trait::async_trait;
+use futures::StreamExt;
+use streamline::{State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn runs_state_hooks() {
+    #[derive(Clone)]
+    struct Context {
+        visits: Vec<&'static str>,
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        Middle,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = Context;
+        type Error = ();
+
+        async fn next(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            let next_state = match self {
+                MyState::Start => Some(Self::Middle),
+                MyState::Middle => Some(Self::End),
+                MyState::End => None,
+            };
+
+            Ok(next_state)
+        }
+    }
+
+    let record = |visit| {
+        move |context: &mut Option<Context>| {
+            if let Some(context) = context {
+                context.visits.push(visit)
+            }
+        }
+    };
+
+    Runtime::new().unwrap().block_on(async {
+        let mut stream = Box::pin(
+            Streamline::build(MyState::Start)
+                .context(Context { visits: vec![] })
+                .on_state(MyState::Start, record("start"))
+                .on_state(MyState::End, record("first end"))
+                .on_state(MyState::End, record("second end"))
+                .run_with_snapshots(),
+        );
+
+        let mut last_context = None;
+
+        while let Some((_, context)) = stream.next().await {
+            last_context = context;
+        }
+
+        let visits = last_context.expect("missing final context").visits;
+
+        assert_eq!(visits, ["first end", "second end"]);
+    });
+}
@@ -0,0 +1,81 @@
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+use streamline::{MemoryStore, Progress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn skips_completed_states() {
+    static NEXT_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Debug, PartialEq)]
+    struct Context;
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        Middle,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = Context;
+        type Error = ();
+
+        async fn next(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            NEXT_CALLS.fetch_add(1, Ordering::SeqCst);
+
+            let next_state = match self {
+                MyState::Start => Some(Self::Middle),
+                MyState::Middle => Some(Self::End),
+                _ => None,
+            };
+
+            Ok(next_state)
+        }
+
+        fn idempotency_key(&self) -> Option<String> {
+            match self {
+                MyState::Start => Some("start".into()),
+                MyState::Middle => Some("middle".into()),
+                MyState::End => None,
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let store = Arc::new(MemoryStore::new());
+
+        let first_run: Vec<_> = Streamline::build(MyState::Start)
+            .context(Context)
+            .idempotent(store.clone())
+            .run()
+            .collect()
+            .await;
+
+        assert_eq!(NEXT_CALLS.load(Ordering::SeqCst), 3);
+
+        let second_run: Vec<_> = Streamline::build(MyState::Start)
+            .context(Context)
+            .idempotent(store)
+            .run()
+            .collect()
+            .await;
+
+        // only the un-keyed terminal state runs again
+        assert_eq!(NEXT_CALLS.load(Ordering::SeqCst), 4);
+        assert_eq!(first_run, second_run);
+
+        match second_run.last() {
+            Some(Progress::Ok(state)) => assert_eq!(state, &MyState::End),
+            _ => panic!("incorrect terminal state found"),
+        }
+    });
+}
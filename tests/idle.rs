@@ -0,0 +1,72 @@
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::time::{Duration, Instant};
+use streamline::{Progress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn announces_idle_states_before_waiting() {
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        Scheduled(Instant),
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = ();
+        type Error = ();
+
+        async fn next(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::Start => Ok(Some(MyState::Scheduled(
+                    Instant::now() + Duration::from_millis(200),
+                ))),
+                MyState::Scheduled(_) => Ok(Some(MyState::End)),
+                MyState::End => Ok(None),
+            }
+        }
+
+        fn delay_until(&self) -> Option<Instant> {
+            match self {
+                MyState::Scheduled(until) => Some(*until),
+                _ => None,
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let started = Instant::now();
+
+        let states: Vec<_> = Streamline::build(MyState::Start)
+            .emit_idle_marker()
+            .run()
+            .map(|progress| (progress, started.elapsed()))
+            .collect()
+            .await;
+
+        let until = match &states[1].0 {
+            Progress::Ok(MyState::Scheduled(until)) => *until,
+            progress => panic!("unexpected state {:?}", progress),
+        };
+
+        assert_eq!(
+            states
+                .iter()
+                .map(|(progress, _)| progress)
+                .collect::<Vec<_>>(),
+            [
+                &Progress::Ok(MyState::Start),
+                &Progress::Ok(MyState::Scheduled(until)),
+                &Progress::Idle {
+                    state: MyState::Scheduled(until)
+                },
+                &Progress::Ok(MyState::End),
+            ]
+        );
+
+        assert!(states[2].1 < Duration::from_millis(100));
+        assert!(states[3].1 >= Duration::from_millis(200));
+    });
+}
@@ -0,0 +1,42 @@
+use async_trait::async_trait;
+use futures::StreamExt;
+use streamline::{Progress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn ends_on_final_states() {
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = ();
+        type Error = ();
+
+        async fn next(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::Start => Ok(Some(Self::End)),
+                MyState::End => panic!("next called on a final state"),
+            }
+        }
+
+        fn is_final(&self) -> bool {
+            self == &MyState::End
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let progress: Vec<_> = Streamline::build(MyState::Start).run().collect().await;
+
+        assert_eq!(
+            progress,
+            [Progress::Ok(MyState::Start), Progress::Ok(MyState::End)]
+        );
+    });
+}
@@ -0,0 +1,44 @@
+#![cfg(feature = "testing")]
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::time::{Duration, Instant};
+use streamline::{State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn delays_transitions() {
+    #[derive(Clone, Debug, PartialEq)]
+    struct MyState(u32);
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = ();
+        type Error = ();
+
+        async fn next(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            match self.0 {
+                3 => Ok(None),
+                count => Ok(Some(Self(count + 1))),
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let start = Instant::now();
+
+        let progress: Vec<_> = Streamline::build(MyState(0))
+            .with_jitter(Duration::from_millis(10), Duration::from_millis(20))
+            .seeded(42)
+            .run()
+            .collect()
+            .await;
+
+        let elapsed = start.elapsed();
+
+        assert_eq!(progress.len(), 4);
+        assert!(elapsed >= Duration::from_millis(40));
+    });
+}
@@ -0,0 +1,80 @@
+#![cfg(all(feature = "serde", feature = "revert"))]
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::fmt;
+use streamline::{State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn emits_json_lines() {
+    struct Context;
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        Middle(String),
+    }
+
+    impl fmt::Display for MyState {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                MyState::Start => f.write_str("starting"),
+                MyState::Middle(message) => write!(f, "halfway: {}", message),
+            }
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct MyError(&'static str);
+
+    impl fmt::Display for MyError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(self.0)
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = Context;
+        type Error = MyError;
+
+        async fn next(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::Start => Ok(Some(Self::Middle("hooray!".into()))),
+                MyState::Middle(_) => Err(MyError("Something went wrong!")),
+            }
+        }
+
+        async fn revert(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::Middle(_) => Ok(Some(Self::Start)),
+                MyState::Start => Err(MyError("Could not revert!")),
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let lines: Vec<_> = Streamline::build(MyState::Start)
+            .context(Context)
+            .run_jsonl()
+            .collect()
+            .await;
+
+        assert_eq!(
+            lines,
+            [
+                r#"{"phase":"ok","state":"starting"}"#,
+                r#"{"phase":"ok","state":"halfway: hooray!"}"#,
+                r#"{"phase":"reverting","source":"Something went wrong!","state":"halfway: hooray!"}"#,
+                r#"{"phase":"reverting","source":"Something went wrong!","state":"starting"}"#,
+                r#"{"error":"Could not revert!","phase":"failure","source":"Something went wrong!"}"#,
+            ]
+        );
+    });
+}
@@ -0,0 +1,68 @@
+use async_trait::async_trait;
+use futures::{future::Either, StreamExt};
+use std::time::{Duration, Instant};
+use streamline::{KeepAlive, Progress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn pings_while_idle() {
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        Scheduled(Instant),
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = ();
+        type Error = ();
+
+        async fn next(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::Start => Ok(Some(MyState::Scheduled(
+                    Instant::now() + Duration::from_millis(350),
+                ))),
+                MyState::Scheduled(_) => Ok(Some(MyState::End)),
+                MyState::End => Ok(None),
+            }
+        }
+
+        fn delay_until(&self) -> Option<Instant> {
+            match self {
+                MyState::Scheduled(until) => Some(*until),
+                _ => None,
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let items: Vec<_> = Streamline::build(MyState::Start)
+            .run_with_keepalive(Duration::from_millis(100))
+            .collect()
+            .await;
+
+        let states: Vec<_> = items
+            .iter()
+            .filter_map(|item| match item {
+                Either::Left(Progress::Ok(state)) => Some(state),
+                _ => None,
+            })
+            .collect();
+
+        assert!(matches!(
+            states[..],
+            [MyState::Start, MyState::Scheduled(_), MyState::End]
+        ));
+
+        // states are only emitted once the transition out of them completes, so the markers
+        // come right before the state that is idling
+        let keepalives = items[1..items.len() - 2]
+            .iter()
+            .filter(|item| matches!(item, Either::Right(KeepAlive)))
+            .count();
+
+        assert_eq!(keepalives, items.len() - 3);
+        assert!((2..=4).contains(&keepalives));
+    });
+}
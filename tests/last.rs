@@ -0,0 +1,54 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use streamline::{Progress, RevertProgress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn returns_last_state() {
+    #[derive(Debug)]
+    struct Context;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct MyState(u32);
+
+    #[derive(Debug, PartialEq)]
+    struct MyError(&'static str);
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = Context;
+        type Error = MyError;
+
+        async fn next(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            match self.0 {
+                1000 => Err(MyError("Something went wrong!")),
+                count => Ok(Some(Self(count + 1))),
+            }
+        }
+
+        async fn revert(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            match self.0 {
+                0 => Ok(None),
+                count => Ok(Some(Self(count - 1))),
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let last = Streamline::build(MyState(0)).context(Context).last().await;
+
+        match last {
+            Some(Progress::Revert(RevertProgress::Reverted {
+                source: Some(source),
+                ..
+            })) => assert_eq!(*source, MyError("Something went wrong!")),
+            _ => panic!("incorrect terminal state found"),
+        }
+    });
+}
@@ -0,0 +1,90 @@
+#![cfg(all(feature = "log", feature = "revert"))]
+use async_trait::async_trait;
+use futures::StreamExt;
+use lazy_static::lazy_static;
+use log::{Level, Log, Metadata, Record};
+use std::{fmt, sync::Mutex};
+use streamline::{State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn logs_progress() {
+    lazy_static! {
+        static ref LINES: Mutex<Vec<String>> = Mutex::new(vec![]);
+    }
+
+    struct Logger;
+
+    impl Log for Logger {
+        fn enabled(&self, _metadata: &Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &Record) {
+            LINES
+                .lock()
+                .expect("could not get lock on log lines")
+                .push(format!("{} {}", record.level(), record.args()));
+        }
+
+        fn flush(&self) {}
+    }
+
+    struct Context;
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        End,
+    }
+
+    impl fmt::Display for MyState {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                MyState::Start => f.write_str("starting"),
+                MyState::End => f.write_str("finishing"),
+            }
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = Context;
+        type Error = &'static str;
+
+        async fn next(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::Start => Ok(Some(Self::End)),
+                MyState::End => Err("Something went wrong!"),
+            }
+        }
+    }
+
+    log::set_logger(&Logger).expect("could not set logger");
+    log::set_max_level(log::LevelFilter::Info);
+
+    Runtime::new().unwrap().block_on(async {
+        Streamline::build(MyState::Start)
+            .context(Context)
+            .with_name("machine")
+            .log_progress(Level::Info)
+            .run()
+            .collect::<Vec<_>>()
+            .await;
+
+        let lines = LINES.lock().expect("could not get lock on log lines");
+
+        assert_eq!(
+            *lines,
+            [
+                "INFO machine: ok: starting",
+                "INFO machine: ok: finishing",
+                "WARN machine: reverting: finishing",
+                "WARN machine: reverted: Something went wrong!",
+            ]
+        );
+    });
+}
@@ -0,0 +1,78 @@
+#![cfg(feature = "log")]
+use async_trait::async_trait;
+use futures::StreamExt;
+use lazy_static::lazy_static;
+use log::{Log, Metadata, Record};
+use std::sync::Mutex;
+use streamline::{decorators::Logged, Progress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn logs_transitions() {
+    lazy_static! {
+        static ref LINES: Mutex<Vec<String>> = Mutex::new(vec![]);
+    }
+
+    struct Logger;
+
+    impl Log for Logger {
+        fn enabled(&self, _metadata: &Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &Record) {
+            LINES
+                .lock()
+                .expect("could not get lock on log lines")
+                .push(format!("{} {}", record.level(), record.args()));
+        }
+
+        fn flush(&self) {}
+    }
+
+    struct Context;
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = Context;
+        type Error = ();
+
+        async fn next(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            let next_state = match self {
+                MyState::Start => Some(Self::End),
+                _ => None,
+            };
+
+            Ok(next_state)
+        }
+    }
+
+    log::set_logger(&Logger).expect("could not set logger");
+    log::set_max_level(log::LevelFilter::Info);
+
+    Runtime::new().unwrap().block_on(async {
+        let states: Vec<_> = Streamline::build(Logged::new(MyState::Start))
+            .context(Context)
+            .run()
+            .collect()
+            .await;
+
+        match states.last() {
+            Some(Progress::Ok(state)) => assert_eq!(state.inner(), &MyState::End),
+            _ => panic!("incorrect terminal state found"),
+        }
+
+        let lines = LINES.lock().expect("could not get lock on log lines");
+
+        assert_eq!(*lines, ["INFO Start -> End", "INFO End: finished"]);
+    });
+}
@@ -0,0 +1,66 @@
+use async_trait::async_trait;
+use futures::{future::Either, StreamExt};
+use streamline::{LogCapture, LogLine, Progress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[derive(Clone, Debug, PartialEq)]
+enum MyState {
+    Start,
+    Middle,
+    End,
+}
+
+#[async_trait(?Send)]
+impl State for MyState {
+    type Context = LogCapture<()>;
+    type Error = ();
+
+    async fn next(&self, context: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+        let context = context.unwrap();
+
+        let next_state = match self {
+            MyState::Start => {
+                context.log("starting");
+                context.log("moving to Middle");
+
+                Some(Self::Middle)
+            }
+            MyState::Middle => Some(Self::End),
+            MyState::End => {
+                context.log("done");
+
+                None
+            }
+        };
+
+        Ok(next_state)
+    }
+}
+
+#[test]
+fn interleaves_logs_with_progress() {
+    Runtime::new().unwrap().block_on(async {
+        let timeline: Vec<_> = Streamline::build(MyState::Start)
+            .context(LogCapture::new(()))
+            .run_with_logs()
+            .map(|item| match item {
+                Either::Left(Progress::Ok(state)) => format!("{:?}", state),
+                Either::Left(progress) => panic!("unexpected progress {:?}", progress),
+                Either::Right(LogLine { message }) => message,
+            })
+            .collect()
+            .await;
+
+        assert_eq!(
+            timeline,
+            [
+                "Start",
+                "starting",
+                "moving to Middle",
+                "Middle",
+                "End",
+                "done"
+            ]
+        );
+    });
+}
@@ -0,0 +1,102 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use futures::StreamExt;
+use streamline::{Outcome, Progress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[derive(Debug, PartialEq)]
+struct Basic {
+    user: Option<&'static str>,
+}
+
+#[derive(Debug, PartialEq)]
+struct Rich {
+    user: &'static str,
+    greeting: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Login {
+    Start,
+    End,
+}
+
+#[async_trait(?Send)]
+impl State for Login {
+    type Context = Basic;
+    type Error = &'static str;
+
+    async fn next(&self, context: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+        let context = context.ok_or("Missing context")?;
+
+        match self {
+            Login::Start => {
+                context.user = Some("ferris");
+
+                Ok(Some(Login::End))
+            }
+            Login::End => Ok(None),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Greet {
+    Start,
+    End,
+}
+
+#[async_trait(?Send)]
+impl State for Greet {
+    type Context = Rich;
+    type Error = &'static str;
+
+    async fn next(&self, context: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+        let context = context.ok_or("Missing context")?;
+
+        match self {
+            Greet::Start => {
+                context.greeting = Some(format!("hello, {}!", context.user));
+
+                Ok(Some(Greet::End))
+            }
+            Greet::End => Ok(None),
+        }
+    }
+}
+
+#[test]
+fn upgrades_context_between_stages() {
+    Runtime::new().unwrap().block_on(async {
+        let greet = Streamline::build(Login::Start)
+            .context(Basic { user: None })
+            .map_context(Greet::Start, |basic| Rich {
+                user: basic.user.unwrap_or("anonymous"),
+                greeting: None,
+            })
+            .await
+            .expect("login failed");
+
+        let states: Vec<_> = greet
+            .finally(|_, context| {
+                assert_eq!(
+                    context.and_then(|context| context.greeting.as_deref()),
+                    Some("hello, ferris!")
+                );
+            })
+            .run()
+            .collect()
+            .await;
+
+        assert_eq!(
+            states,
+            [Progress::Ok(Greet::Start), Progress::Ok(Greet::End)]
+        );
+
+        let failed = Streamline::build(Login::Start)
+            .map_context(Greet::Start, |_: Basic| unreachable!())
+            .await;
+
+        assert!(matches!(failed, Err(Outcome::Reverted { .. })));
+    });
+}
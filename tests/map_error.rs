@@ -0,0 +1,53 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use futures::StreamExt;
+use streamline::{Progress, RevertProgress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn maps_errors_before_reverting() {
+    #[derive(Debug)]
+    struct Context;
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        Middle,
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct MyError(String);
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = Context;
+        type Error = MyError;
+
+        async fn next(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::Start => Ok(Some(Self::Middle)),
+                MyState::Middle => Err(MyError("Something went wrong!".into())),
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let states: Vec<_> = Streamline::build(MyState::Start)
+            .context(Context)
+            .map_error_on_revert(|error, state| MyError(format!("{:?}: {}", state, error.0)))
+            .run()
+            .collect()
+            .await;
+
+        match states.last() {
+            Some(Progress::Revert(RevertProgress::Reverted {
+                source: Some(source),
+                ..
+            })) => assert_eq!(**source, MyError("Middle: Something went wrong!".into())),
+            _ => panic!("incorrect terminal state found"),
+        }
+    });
+}
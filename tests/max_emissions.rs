@@ -0,0 +1,48 @@
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::{cell::Cell, rc::Rc};
+use streamline::{Progress, State, Streamline, TerminalOutcome};
+use tokio::runtime::Runtime;
+
+#[derive(Clone, Debug, PartialEq)]
+struct Counter(usize);
+
+#[async_trait(?Send)]
+impl State for Counter {
+    type Context = ();
+    type Error = ();
+
+    async fn next(
+        &self,
+        _context: Option<&mut Self::Context>,
+    ) -> Result<Option<Self>, Self::Error> {
+        Ok(Some(Counter(self.0 + 1)))
+    }
+}
+
+#[test]
+fn truncates_runaway_machines() {
+    Runtime::new().unwrap().block_on(async {
+        let outcome = Rc::new(Cell::new(None));
+        let finally_outcome = outcome.clone();
+
+        let states: Vec<_> = Streamline::build(Counter(0))
+            .max_emissions(3)
+            .finally(move |outcome, _| finally_outcome.set(Some(outcome)))
+            .run()
+            .collect()
+            .await;
+
+        assert_eq!(
+            states,
+            [
+                Progress::Ok(Counter(0)),
+                Progress::Ok(Counter(1)),
+                Progress::Ok(Counter(2)),
+                Progress::Truncated,
+            ]
+        );
+
+        assert_eq!(outcome.get(), Some(TerminalOutcome::Cancelled));
+    });
+}
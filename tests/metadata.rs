@@ -0,0 +1,54 @@
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::collections::HashMap;
+use streamline::{Progress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn emits_metadata_with_progress() {
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = ();
+        type Error = ();
+
+        async fn next(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::Start => Ok(Some(MyState::End)),
+                MyState::End => Ok(None),
+            }
+        }
+    }
+
+    let metadata: HashMap<_, _> = vec![("request_id".to_string(), "abc123".to_string())]
+        .into_iter()
+        .collect();
+
+    Runtime::new().unwrap().block_on(async {
+        let items: Vec<_> = Streamline::build(MyState::Start)
+            .with_metadata(metadata.clone())
+            .run_with_metadata()
+            .collect()
+            .await;
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].0, Progress::Ok(MyState::Start));
+        assert_eq!(items[1].0, Progress::Ok(MyState::End));
+
+        for (_, item_metadata) in &items {
+            assert_eq!(**item_metadata, metadata);
+        }
+
+        let empty: Vec<_> = Streamline::build(MyState::End)
+            .run_with_metadata()
+            .collect()
+            .await;
+
+        assert!(empty[0].1.is_empty());
+    });
+}
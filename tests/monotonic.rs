@@ -0,0 +1,63 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use futures::StreamExt;
+use streamline::{NotMonotonic, Progress, RevertProgress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn reverts_backward_transitions() {
+    #[derive(Debug, PartialEq)]
+    enum MyError {
+        NotMonotonic,
+    }
+
+    impl From<NotMonotonic> for MyError {
+        fn from(_: NotMonotonic) -> Self {
+            MyError::NotMonotonic
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, PartialOrd)]
+    struct Version(u32);
+
+    #[async_trait(?Send)]
+    impl State for Version {
+        type Context = ();
+        type Error = MyError;
+
+        async fn next(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+            match self.0 {
+                1 => Ok(Some(Version(2))),
+                _ => Ok(Some(Version(1))),
+            }
+        }
+
+        async fn revert(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+            Ok(None)
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let states: Vec<_> = Streamline::build(Version(1))
+            .enforce_monotonic()
+            .run()
+            .collect()
+            .await;
+
+        assert_eq!(
+            states,
+            [
+                Progress::Ok(Version(1)),
+                Progress::Ok(Version(2)),
+                Progress::Revert(RevertProgress::Reverting {
+                    step: Version(2),
+                    source: Some(MyError::NotMonotonic.into()),
+                }),
+                Progress::Revert(RevertProgress::Reverted {
+                    source: Some(MyError::NotMonotonic.into()),
+                    compensated_states: vec![],
+                }),
+            ]
+        );
+    });
+}
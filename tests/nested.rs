@@ -0,0 +1,92 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use futures::StreamExt;
+use streamline::{Outcome, Progress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[derive(Clone, Debug, PartialEq)]
+struct Child(u32);
+
+#[derive(Debug, PartialEq)]
+struct ChildError;
+
+#[async_trait(?Send)]
+impl State for Child {
+    type Context = ();
+    type Error = ChildError;
+
+    async fn next(
+        &self,
+        _context: Option<&mut Self::Context>,
+    ) -> Result<Option<Self>, Self::Error> {
+        match self.0 {
+            3 => Ok(None),
+            13 => Err(ChildError),
+            count => Ok(Some(Self(count + 1))),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum ParentError {
+    Child,
+}
+
+impl From<Outcome<Child, ChildError>> for ParentError {
+    fn from(_outcome: Outcome<Child, ChildError>) -> Self {
+        Self::Child
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Parent {
+    Start(u32),
+    End(u32),
+}
+
+#[async_trait(?Send)]
+impl State for Parent {
+    type Context = ();
+    type Error = ParentError;
+
+    async fn next(
+        &self,
+        _context: Option<&mut Self::Context>,
+    ) -> Result<Option<Self>, Self::Error> {
+        match self {
+            Parent::Start(start) => {
+                let child = Streamline::build(Child(*start))
+                    .run_nested::<ParentError>()
+                    .await?;
+
+                Ok(child.map(|child| Parent::End(child.0)))
+            }
+            Parent::End(_) => Ok(None),
+        }
+    }
+}
+
+#[test]
+fn runs_nested_machines() {
+    Runtime::new().unwrap().block_on(async {
+        let completed: Vec<_> = Streamline::build(Parent::Start(0)).run().collect().await;
+
+        assert_eq!(
+            completed,
+            [Progress::Ok(Parent::Start(0)), Progress::Ok(Parent::End(3))]
+        );
+
+        let reverted = Streamline::build(Parent::Start(10)).outcome().await;
+
+        match reverted {
+            Some(Outcome::Reverted {
+                source: Some(source),
+            }) => assert_eq!(*source, ParentError::Child),
+            _ => panic!("incorrect outcome found"),
+        }
+
+        let child = Streamline::build(Child(10)).outcome().await;
+
+        assert!(matches!(child, Some(Outcome::Reverted { .. })));
+    });
+}
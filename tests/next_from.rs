@@ -0,0 +1,69 @@
+use async_trait::async_trait;
+use futures::StreamExt;
+use streamline::{Progress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn passes_the_previous_state() {
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        Left,
+        Right,
+        Junction,
+        FromLeft,
+        FromRight,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = ();
+        type Error = ();
+
+        async fn next(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+            unreachable!("Streamlines should call next_from")
+        }
+
+        async fn next_from(
+            &self,
+            previous: Option<&Self>,
+            _: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            let next = match (self, previous) {
+                (MyState::Start, None) => Some(MyState::Left),
+                (MyState::Left, Some(MyState::Start)) => Some(MyState::Junction),
+                (MyState::Right, _) => Some(MyState::Junction),
+                (MyState::Junction, Some(MyState::Left)) => Some(MyState::FromLeft),
+                (MyState::Junction, Some(MyState::Right)) => Some(MyState::FromRight),
+                _ => None,
+            };
+
+            Ok(next)
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let states: Vec<_> = Streamline::build(MyState::Start).run().collect().await;
+
+        assert_eq!(
+            states,
+            [
+                Progress::Ok(MyState::Start),
+                Progress::Ok(MyState::Left),
+                Progress::Ok(MyState::Junction),
+                Progress::Ok(MyState::FromLeft),
+            ]
+        );
+
+        let states: Vec<_> = Streamline::build(MyState::Right).run().collect().await;
+
+        assert_eq!(
+            states,
+            [
+                Progress::Ok(MyState::Right),
+                Progress::Ok(MyState::Junction),
+                Progress::Ok(MyState::FromRight),
+            ]
+        );
+    });
+}
@@ -0,0 +1,48 @@
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::{cell::RefCell, panic, rc::Rc};
+use streamline::{State, Streamline};
+use tokio::runtime::Runtime;
+
+#[derive(Clone, Debug, PartialEq)]
+enum MyState {
+    Start,
+    Middle,
+}
+
+#[async_trait(?Send)]
+impl State for MyState {
+    type Context = ();
+    type Error = ();
+
+    async fn next(
+        &self,
+        _context: Option<&mut Self::Context>,
+    ) -> Result<Option<Self>, Self::Error> {
+        match self {
+            MyState::Start => Ok(Some(MyState::Middle)),
+            MyState::Middle => panic!("Middle blew up"),
+        }
+    }
+}
+
+#[test]
+fn reports_panicking_states_before_re_panicking() {
+    let panicked = Rc::new(RefCell::new(vec![]));
+    let hook_panicked = panicked.clone();
+
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        Runtime::new().unwrap().block_on(async {
+            Streamline::build(MyState::Start)
+                .on_panic(move |state| hook_panicked.borrow_mut().push(state.clone()))
+                .run()
+                .collect::<Vec<_>>()
+                .await
+        })
+    }));
+
+    let payload = result.expect_err("panic was not re-raised");
+
+    assert_eq!(payload.downcast_ref::<&str>(), Some(&"Middle blew up"));
+    assert_eq!(*panicked.borrow(), [MyState::Middle]);
+}
@@ -0,0 +1,120 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use futures::{future, StreamExt};
+use std::{cell::RefCell, task::Poll};
+use streamline::{Progress, RevertProgress, State, Streamline};
+use tokio::runtime::Runtime;
+
+thread_local! {
+    static LOG: RefCell<Vec<String>> = const { RefCell::new(vec![]) };
+}
+
+fn log(entry: String) {
+    LOG.with(|log| log.borrow_mut().push(entry));
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum MyState {
+    Start,
+    Create(u8),
+}
+
+#[async_trait(?Send)]
+impl State for MyState {
+    type Context = ();
+    type Error = &'static str;
+
+    async fn next(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+        match self {
+            MyState::Start => Ok(Some(MyState::Create(0))),
+            MyState::Create(2) => Err("Could not create resource"),
+            MyState::Create(index) => Ok(Some(MyState::Create(index + 1))),
+        }
+    }
+
+    async fn revert(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+        log(format!("start {:?}", self));
+
+        // yield once so that other reverts get a chance to start
+        let mut yielded = false;
+
+        future::poll_fn(|cx| {
+            if yielded {
+                Poll::Ready(())
+            } else {
+                yielded = true;
+                cx.waker().wake_by_ref();
+
+                Poll::Pending
+            }
+        })
+        .await;
+
+        log(format!("end {:?}", self));
+
+        Ok(None)
+    }
+
+    fn independent_revert(&self) -> bool {
+        matches!(self, MyState::Create(_))
+    }
+}
+
+#[test]
+fn reverts_independent_states_concurrently() {
+    Runtime::new().unwrap().block_on(async {
+        let states: Vec<_> = Streamline::build(MyState::Start)
+            .parallel_revert()
+            .run()
+            .collect()
+            .await;
+
+        let source = Some("Could not create resource".into());
+
+        assert_eq!(
+            states,
+            [
+                Progress::Ok(MyState::Start),
+                Progress::Ok(MyState::Create(0)),
+                Progress::Ok(MyState::Create(1)),
+                Progress::Ok(MyState::Create(2)),
+                Progress::Revert(RevertProgress::Reverting {
+                    step: MyState::Create(2),
+                    source: source.clone(),
+                }),
+                Progress::Revert(RevertProgress::Reverting {
+                    step: MyState::Create(1),
+                    source: source.clone(),
+                }),
+                Progress::Revert(RevertProgress::Reverting {
+                    step: MyState::Create(0),
+                    source: source.clone(),
+                }),
+                Progress::Revert(RevertProgress::Reverting {
+                    step: MyState::Start,
+                    source: source.clone(),
+                }),
+                Progress::Revert(RevertProgress::Reverted {
+                    source,
+                    compensated_states: vec![],
+                }),
+            ]
+        );
+
+        LOG.with(|log| {
+            assert_eq!(
+                *log.borrow(),
+                [
+                    "start Create(2)",
+                    "start Create(1)",
+                    "start Create(0)",
+                    "end Create(2)",
+                    "end Create(1)",
+                    "end Create(0)",
+                    "start Start",
+                    "end Start",
+                ]
+            )
+        });
+    });
+}
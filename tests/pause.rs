@@ -0,0 +1,114 @@
+use async_trait::async_trait;
+use futures::{join, StreamExt};
+use futures_timer::Delay;
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+use streamline::{Progress, SharedContext, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn pauses_while_predicate_holds() {
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = Arc<AtomicBool>;
+        type Error = ();
+
+        async fn next(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::Start => Ok(Some(MyState::End)),
+                MyState::End => Ok(None),
+            }
+        }
+    }
+
+    let overloaded = Arc::new(AtomicBool::new(true));
+
+    Runtime::new().unwrap().block_on(async {
+        let started = Instant::now();
+
+        let stream = Streamline::build(MyState::Start)
+            .context(overloaded.clone())
+            .pause_when(
+                |overloaded| overloaded.load(Ordering::SeqCst),
+                Duration::from_millis(5),
+            )
+            .run();
+
+        let relieve = async {
+            Delay::new(Duration::from_millis(50)).await;
+            overloaded.store(false, Ordering::SeqCst);
+        };
+
+        let (states, ()) = join!(stream.collect::<Vec<_>>(), relieve);
+
+        assert!(matches!(
+            states.as_slice(),
+            [Progress::Ok(MyState::Start), Progress::Ok(MyState::End)]
+        ));
+        assert!(started.elapsed() >= Duration::from_millis(50));
+    });
+}
+
+#[test]
+fn resumes_shared_contexts() {
+    #[derive(Debug)]
+    struct Context {
+        overloaded: bool,
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = Context;
+        type Error = ();
+
+        async fn next(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::Start => Ok(Some(MyState::End)),
+                MyState::End => Ok(None),
+            }
+        }
+    }
+
+    let shared = SharedContext::new(Context { overloaded: true });
+
+    Runtime::new().unwrap().block_on(async {
+        let stream = Streamline::build(MyState::Start)
+            .share_context(shared.clone())
+            .pause_when(|context| context.overloaded, Duration::from_millis(5))
+            .run();
+
+        // the paused Streamline must not hold the lock while it waits
+        let relieve = async {
+            Delay::new(Duration::from_millis(20)).await;
+            shared.with(|context| context.overloaded = false).await;
+        };
+
+        let (states, ()) = join!(stream.collect::<Vec<_>>(), relieve);
+
+        assert!(matches!(
+            states.as_slice(),
+            [Progress::Ok(MyState::Start), Progress::Ok(MyState::End)]
+        ));
+    });
+
+    let context = shared.try_unwrap().expect("context is still shared");
+
+    assert!(!context.overloaded);
+}
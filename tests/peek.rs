@@ -0,0 +1,55 @@
+use async_trait::async_trait;
+use futures::StreamExt;
+use streamline::{Progress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn peeks_without_advancing() {
+    #[derive(Clone, Debug, PartialEq)]
+    struct Context {
+        items: Vec<u8>,
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = Context;
+        type Error = ();
+
+        async fn next(
+            &self,
+            context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            context.ok_or(())?.items.push(0);
+
+            let next_state = match self {
+                MyState::Start => Some(Self::End),
+                MyState::End => None,
+            };
+
+            Ok(next_state)
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let streamline = Streamline::build(MyState::Start).context(Context { items: vec![] });
+
+        assert_eq!(streamline.peek_next().await, Ok(Some(MyState::End)));
+        assert_eq!(streamline.peek_next().await, Ok(Some(MyState::End)));
+
+        let snapshots: Vec<_> = streamline.run_with_snapshots().collect().await;
+
+        assert_eq!(
+            snapshots.first(),
+            Some(&(
+                Progress::Ok(MyState::Start),
+                Some(Context { items: vec![0] })
+            ))
+        );
+    });
+}
@@ -0,0 +1,68 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::collections::HashMap;
+use streamline::{Phase, State, Streamline, Supervisor};
+use tokio::runtime::Runtime;
+
+#[test]
+fn emits_only_phase_changes() {
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        Middle,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = bool;
+        type Error = ();
+
+        async fn next(
+            &self,
+            context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            let fail = *context.unwrap();
+
+            match self {
+                MyState::Start => Ok(Some(MyState::Middle)),
+                MyState::Middle => Ok(Some(MyState::End)),
+                MyState::End if fail => Err(()),
+                MyState::End => Ok(None),
+            }
+        }
+
+        async fn revert(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::End => Ok(Some(MyState::Middle)),
+                MyState::Middle => Ok(Some(MyState::Start)),
+                MyState::Start => Ok(None),
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let mut supervisor = Supervisor::new();
+
+        let succeeding = supervisor.add(Streamline::build(MyState::Start).context(false));
+        let failing = supervisor.add(Streamline::build(MyState::Start).context(true));
+
+        let mut changes = HashMap::new();
+
+        supervisor
+            .run_phase_changes()
+            .for_each(|(id, phase)| {
+                changes.entry(id).or_insert_with(Vec::new).push(phase);
+
+                async {}
+            })
+            .await;
+
+        assert_eq!(changes[&succeeding], [Phase::Forward]);
+        assert_eq!(
+            changes[&failing],
+            [Phase::Forward, Phase::Reverting, Phase::Reverted]
+        );
+    });
+}
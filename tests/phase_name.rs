@@ -0,0 +1,53 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use futures::StreamExt;
+use streamline::{State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn names_reversion_phases() {
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        Middle,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = ();
+        type Error = &'static str;
+
+        async fn next(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::Start => Ok(Some(Self::Middle)),
+                MyState::Middle => Ok(Some(Self::End)),
+                MyState::End => Err("Something went wrong!"),
+            }
+        }
+
+        async fn revert(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::End => Ok(Some(Self::Middle)),
+                MyState::Middle => Err("Could not revert!"),
+                MyState::Start => Ok(None),
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let phases: Vec<_> = Streamline::build(MyState::Start)
+            .revert_stream()
+            .map(|revert_progress| revert_progress.phase_name())
+            .collect()
+            .await;
+
+        assert_eq!(phases, ["reverting", "reverting", "failure"]);
+    });
+}
@@ -0,0 +1,79 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use futures::StreamExt;
+use streamline::{Progress, RevertProgress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn reverts_only_past_the_point_of_no_return() {
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        Commit,
+        End,
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct MyError;
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = bool;
+        type Error = MyError;
+
+        async fn next(
+            &self,
+            context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            let fail_early = *context.unwrap();
+
+            match self {
+                MyState::Start if fail_early => Err(MyError),
+                MyState::Start => Ok(Some(MyState::Commit)),
+                MyState::Commit => Ok(Some(MyState::End)),
+                MyState::End => Err(MyError),
+            }
+        }
+
+        async fn revert(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::End => Ok(Some(MyState::Commit)),
+                MyState::Commit => Ok(Some(MyState::Start)),
+                MyState::Start => Ok(None),
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let early: Vec<_> = Streamline::build(MyState::Start)
+            .context(true)
+            .revert_only_after(|state| *state == MyState::Commit)
+            .run()
+            .collect()
+            .await;
+
+        assert_eq!(
+            early,
+            [
+                Progress::Ok(MyState::Start),
+                Progress::Revert(RevertProgress::Failure {
+                    source: None,
+                    error: MyError,
+                }),
+            ]
+        );
+
+        let late: Vec<_> = Streamline::build(MyState::Start)
+            .context(false)
+            .revert_only_after(|state| *state == MyState::Commit)
+            .run()
+            .collect()
+            .await;
+
+        assert!(matches!(
+            late.last(),
+            Some(Progress::Revert(RevertProgress::Reverted { .. }))
+        ));
+        assert_eq!(late.len(), 7);
+    });
+}
@@ -0,0 +1,96 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use futures_timer::Delay;
+use std::time::Duration;
+use streamline::{race, Outcome, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn returns_the_first_completion() {
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        Finished(u64),
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = Option<u64>;
+        type Error = ();
+
+        async fn next(
+            &self,
+            context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            match (self, context.unwrap()) {
+                (MyState::Start, Some(millis)) => {
+                    Delay::new(Duration::from_millis(*millis)).await;
+
+                    Ok(Some(MyState::Finished(*millis)))
+                }
+                (MyState::Start, None) => Err(()),
+                (MyState::Finished(_), _) => Ok(None),
+            }
+        }
+    }
+
+    let machine = |millis| Streamline::build(MyState::Start).context(millis);
+
+    Runtime::new().unwrap().block_on(async {
+        let winner = race(vec![machine(None), machine(Some(200)), machine(Some(10))]).await;
+
+        assert_eq!(winner, Ok(MyState::Finished(10)));
+
+        let failures = race(vec![machine(None), machine(None)]).await.unwrap_err();
+
+        assert_eq!(failures.len(), 2);
+        assert!(failures
+            .iter()
+            .all(|outcome| matches!(outcome, Outcome::Reverted { source: Some(_) })));
+    });
+}
+
+#[test]
+fn ignores_truncated_competitors() {
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        Working(u64),
+        Finished(u64),
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = u64;
+        type Error = ();
+
+        async fn next(
+            &self,
+            context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            let millis = *context.unwrap();
+
+            match self {
+                MyState::Start => Ok(Some(MyState::Working(millis))),
+                MyState::Working(_) => {
+                    Delay::new(Duration::from_millis(millis)).await;
+
+                    Ok(Some(MyState::Finished(millis)))
+                }
+                MyState::Finished(_) => Ok(None),
+            }
+        }
+    }
+
+    let machine = |millis| Streamline::build(MyState::Start).context(millis);
+
+    Runtime::new().unwrap().block_on(async {
+        let winner = race(vec![machine(100), machine(10).max_emissions(1)]).await;
+
+        assert_eq!(winner, Ok(MyState::Finished(100)));
+
+        let failures = race(vec![machine(10).max_emissions(2)]).await.unwrap_err();
+
+        assert!(failures.is_empty());
+    });
+}
@@ -0,0 +1,64 @@
+use async_trait::async_trait;
+use futures::StreamExt;
+use streamline::{Progress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[derive(Clone, Debug, PartialEq)]
+enum MyState {
+    Start,
+    Middle(u8),
+    End(u8),
+}
+
+#[async_trait(?Send)]
+impl State for MyState {
+    type Context = ();
+    type Error = ();
+
+    async fn next(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+        match self {
+            MyState::Start => Ok(Some(MyState::Middle(1))),
+            MyState::Middle(value) => Ok(Some(MyState::End(value * 2))),
+            MyState::End(_) => Ok(None),
+        }
+    }
+}
+
+#[test]
+fn starts_mid_machine() {
+    Runtime::new().unwrap().block_on(async {
+        let states: Vec<_> = Streamline::from_state(MyState::Middle(21))
+            .run()
+            .collect()
+            .await;
+
+        assert_eq!(
+            states,
+            [
+                Progress::Ok(MyState::Middle(21)),
+                Progress::Ok(MyState::End(42))
+            ]
+        );
+    });
+}
+
+#[test]
+fn replays_from_history() {
+    Runtime::new().unwrap().block_on(async {
+        let history: Vec<_> = Streamline::build(MyState::Start)
+            .emit_completion_marker()
+            .run()
+            .collect()
+            .await;
+
+        let replayed: Vec<_> = Streamline::replay_from(&history, 1)
+            .expect("no state to replay from")
+            .run()
+            .collect()
+            .await;
+
+        assert_eq!(replayed, history[1..3]);
+        assert!(Streamline::replay_from(&history, 3).is_none());
+        assert!(Streamline::replay_from(&history, 4).is_none());
+    });
+}
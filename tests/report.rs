@@ -0,0 +1,55 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use streamline::{Outcome, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn reports_on_the_whole_run() {
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        Middle,
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct MyError;
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = ();
+        type Error = MyError;
+
+        async fn next(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::Start => Ok(Some(MyState::Middle)),
+                MyState::Middle => Err(MyError),
+            }
+        }
+
+        async fn revert(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::Middle => Ok(Some(MyState::Start)),
+                MyState::Start => Ok(None),
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let report = Streamline::build(MyState::Start).run_report().await;
+
+        assert_eq!(
+            report.path,
+            [
+                MyState::Start,
+                MyState::Middle,
+                MyState::Middle,
+                MyState::Start
+            ]
+        );
+        assert!(matches!(report.outcome, Some(Outcome::Reverted { .. })));
+        assert_eq!(report.transitions, 1);
+        assert_eq!(report.reversions, 2);
+        assert!(report.reverted());
+        assert_eq!(report.error(), Some(&MyError));
+    });
+}
@@ -0,0 +1,136 @@
+use async_trait::async_trait;
+use std::{
+    cell::Cell,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use streamline::{ErrorCategory, Jitter, Progress, RetryPolicy, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn computes_custom_backoff() {
+    #[derive(Debug, PartialEq)]
+    struct Context {
+        attempts: Cell<u32>,
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = Context;
+        type Error = ();
+
+        async fn next(
+            &self,
+            context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            let context = context.ok_or(())?;
+
+            match self {
+                MyState::Start => {
+                    context.attempts.set(context.attempts.get() + 1);
+
+                    if context.attempts.get() < 3 {
+                        Err(())
+                    } else {
+                        Ok(Some(MyState::End))
+                    }
+                }
+                MyState::End => Ok(None),
+            }
+        }
+
+        fn categorize(&self, _: &Self::Error) -> ErrorCategory {
+            ErrorCategory::Retry
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let backoffs = Arc::new(Mutex::new(vec![]));
+
+        let policy = RetryPolicy::new(5).jitter(Jitter::Equal).custom({
+            let backoffs = backoffs.clone();
+
+            move |attempt| {
+                backoffs.lock().unwrap().push(attempt);
+
+                Duration::from_millis(20)
+            }
+        });
+
+        let started = Instant::now();
+
+        let last = Streamline::build(MyState::Start)
+            .context(Context {
+                attempts: Cell::new(0),
+            })
+            .retry(policy)
+            .last()
+            .await;
+
+        assert_eq!(last, Some(Progress::Ok(MyState::End)));
+        assert_eq!(*backoffs.lock().unwrap(), [0, 1]);
+
+        // equal jitter always waits for at least half of every delay
+        assert!(started.elapsed() >= Duration::from_millis(20));
+    });
+}
+
+#[test]
+fn caps_exponential_backoff() {
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = u32;
+        type Error = ();
+
+        async fn next(
+            &self,
+            context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            let attempts = context.ok_or(())?;
+
+            match self {
+                MyState::Start => {
+                    *attempts += 1;
+
+                    // enough doublings to overflow any `Duration`
+                    if *attempts <= 70 {
+                        Err(())
+                    } else {
+                        Ok(Some(MyState::End))
+                    }
+                }
+                MyState::End => Ok(None),
+            }
+        }
+
+        fn categorize(&self, _: &Self::Error) -> ErrorCategory {
+            ErrorCategory::Retry
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let policy = RetryPolicy::new(70)
+            .exponential(Duration::from_millis(1))
+            .max_delay(Duration::from_millis(1));
+
+        let last = Streamline::build(MyState::Start)
+            .context(0)
+            .retry(policy)
+            .last()
+            .await;
+
+        assert_eq!(last, Some(Progress::Ok(MyState::End)));
+    });
+}
@@ -0,0 +1,61 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use futures::StreamExt;
+use streamline::{RevertProgress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn yields_only_reversion() {
+    #[derive(Debug, PartialEq)]
+    struct Context;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct MyState(u32);
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = Context;
+        type Error = &'static str;
+
+        async fn next(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            match self.0 {
+                2 => Err("Something went wrong!"),
+                10 => Ok(None),
+                count => Ok(Some(Self(count + 1))),
+            }
+        }
+
+        async fn revert(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            match self.0 {
+                0 => Ok(None),
+                count => Ok(Some(Self(count - 1))),
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let steps: Vec<_> = Streamline::build(MyState(0))
+            .revert_stream()
+            .map(|revert_progress| match revert_progress {
+                RevertProgress::Reverting { step, .. } => Some(step.0),
+                _ => None,
+            })
+            .collect()
+            .await;
+
+        assert_eq!(steps, [Some(2), Some(1), Some(0), None]);
+
+        let completed = Streamline::build(MyState(3))
+            .revert_stream()
+            .collect::<Vec<_>>()
+            .await;
+
+        assert!(completed.is_empty());
+    });
+}
@@ -0,0 +1,91 @@
+#![cfg(feature = "tokio")]
+use async_trait::async_trait;
+use futures::{future, StreamExt};
+use std::{cell::Cell, rc::Rc, sync::Arc, task::Poll};
+use streamline::{State, Streamline, Supervisor};
+use tokio::{runtime::Runtime, sync::Semaphore};
+
+#[test]
+fn limits_concurrent_transitions() {
+    #[derive(Clone)]
+    struct Context {
+        active: Rc<Cell<usize>>,
+        max_active: Rc<Cell<usize>>,
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        Middle,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = Context;
+        type Error = ();
+
+        async fn next(
+            &self,
+            context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            let context = context.ok_or(())?;
+
+            context.active.set(context.active.get() + 1);
+            context
+                .max_active
+                .set(context.max_active.get().max(context.active.get()));
+
+            // yield to the other machines in the middle of the transition
+            let mut yielded = false;
+
+            future::poll_fn(|cx| {
+                if yielded {
+                    Poll::Ready(())
+                } else {
+                    yielded = true;
+                    cx.waker().wake_by_ref();
+                    Poll::Pending
+                }
+            })
+            .await;
+
+            context.active.set(context.active.get() - 1);
+
+            let next_state = match self {
+                MyState::Start => Some(Self::Middle),
+                MyState::Middle => Some(Self::End),
+                MyState::End => None,
+            };
+
+            Ok(next_state)
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let context = Context {
+            active: Rc::new(Cell::new(0)),
+            max_active: Rc::new(Cell::new(0)),
+        };
+
+        let semaphore = Arc::new(Semaphore::new(2));
+
+        let mut supervisor = Supervisor::new();
+
+        for _ in 0..4 {
+            supervisor.add(
+                Streamline::build(MyState::Start)
+                    .context(context.clone())
+                    .with_semaphore(semaphore.clone()),
+            );
+        }
+
+        let (stream, _cancel_all) = supervisor.run_all();
+
+        let progress = stream.collect::<Vec<_>>().await;
+
+        assert_eq!(progress.len(), 12);
+        assert_eq!(context.max_active.get(), 2);
+        assert_eq!(semaphore.available_permits(), 2);
+    });
+}
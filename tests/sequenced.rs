@@ -0,0 +1,60 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use futures::StreamExt;
+use streamline::{Progress, RevertProgress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn sequences_progress() {
+    #[derive(Debug, PartialEq)]
+    struct Context;
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = Context;
+        type Error = ();
+
+        async fn next(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::Start => Ok(Some(Self::End)),
+                MyState::End => Err(()),
+            }
+        }
+
+        async fn revert(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::End => Ok(Some(Self::Start)),
+                MyState::Start => Ok(None),
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let states: Vec<_> = Streamline::build(MyState::Start)
+            .context(Context)
+            .run_sequenced()
+            .collect()
+            .await;
+
+        let sequence: Vec<_> = states.iter().map(|(sequence, _)| *sequence).collect();
+
+        assert_eq!(sequence, [0, 1, 2, 3, 4]);
+
+        assert!(matches!(
+            states.last(),
+            Some((4, Progress::Revert(RevertProgress::Reverted { .. })))
+        ));
+    });
+}
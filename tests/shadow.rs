@@ -0,0 +1,62 @@
+use async_trait::async_trait;
+use futures::StreamExt;
+use streamline::{shadow, Progress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn reports_divergent_implementations() {
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        Middle,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = bool;
+        type Error = ();
+
+        async fn next(
+            &self,
+            context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            let refactored = *context.unwrap();
+
+            match self {
+                MyState::Start => Ok(Some(MyState::Middle)),
+                MyState::Middle if refactored => Ok(None),
+                MyState::Middle => Ok(Some(MyState::End)),
+                MyState::End => Ok(None),
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let items: Vec<_> = shadow(
+            Streamline::build(MyState::Start).context(false),
+            Streamline::build(MyState::Start).context(false),
+        )
+        .collect()
+        .await;
+
+        assert_eq!(items.len(), 3);
+        assert!(items.iter().all(Result::is_ok));
+
+        let items: Vec<_> = shadow(
+            Streamline::build(MyState::Start).context(false),
+            Streamline::build(MyState::Start).context(true),
+        )
+        .collect()
+        .await;
+
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[1].as_ref().ok(), Some(&Progress::Ok(MyState::Middle)));
+
+        let divergence = items[2].as_ref().unwrap_err();
+
+        assert_eq!(divergence.index, 2);
+        assert_eq!(divergence.old, Some(Progress::Ok(MyState::End)));
+        assert_eq!(divergence.new, None);
+    });
+}
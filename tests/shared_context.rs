@@ -0,0 +1,93 @@
+use async_trait::async_trait;
+use futures::{future, StreamExt};
+use streamline::{SharedContext, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn shares_context_between_stages() {
+    #[derive(Debug, PartialEq)]
+    struct Context {
+        log: Vec<&'static str>,
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum Fetch {
+        Start,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for Fetch {
+        type Context = Context;
+        type Error = ();
+
+        async fn next(
+            &self,
+            context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            let context = context.ok_or(())?;
+
+            match self {
+                Fetch::Start => {
+                    context.log.push("fetch");
+
+                    Ok(Some(Self::End))
+                }
+                Fetch::End => Ok(None),
+            }
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum Store {
+        Start,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for Store {
+        type Context = Context;
+        type Error = ();
+
+        async fn next(
+            &self,
+            context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            let context = context.ok_or(())?;
+
+            match self {
+                Store::Start if context.log.contains(&"fetch") => {
+                    context.log.push("store");
+
+                    Ok(Some(Self::End))
+                }
+                Store::Start => Err(()),
+                Store::End => Ok(None),
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let shared = SharedContext::new(Context { log: vec![] });
+
+        Streamline::build(Fetch::Start)
+            .share_context(shared.clone())
+            .run()
+            .for_each(|_| future::ready(()))
+            .await;
+
+        let last = Streamline::build(Store::Start)
+            .share_context(shared.clone())
+            .last()
+            .await;
+
+        assert_eq!(last, Some(streamline::Progress::Ok(Store::End)));
+
+        assert_eq!(
+            shared.try_unwrap().ok(),
+            Some(Context {
+                log: vec!["fetch", "store"]
+            })
+        );
+    });
+}
@@ -0,0 +1,105 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::{cell::RefCell, rc::Rc};
+use streamline::{Progress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn reverts_on_shutdown() {
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        Middle,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = Rc<RefCell<Vec<MyState>>>;
+        type Error = ();
+
+        async fn next(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::Start => Ok(Some(MyState::Middle)),
+                MyState::Middle => Ok(Some(MyState::End)),
+                MyState::End => Ok(None),
+            }
+        }
+
+        async fn revert(
+            &self,
+            context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            context.unwrap().borrow_mut().push(self.clone());
+
+            match self {
+                MyState::End => Ok(Some(MyState::Middle)),
+                MyState::Middle => Ok(Some(MyState::Start)),
+                MyState::Start => Ok(None),
+            }
+        }
+    }
+
+    let reverted = Rc::new(RefCell::new(vec![]));
+
+    Runtime::new().unwrap().block_on(async {
+        Streamline::from_state(MyState::Middle)
+            .context(reverted.clone())
+            .shutdown()
+            .await;
+    });
+
+    assert_eq!(*reverted.borrow(), [MyState::Middle, MyState::Start]);
+}
+
+#[test]
+fn reverts_running_streamlines_on_shutdown() {
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        Middle,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = Rc<RefCell<Vec<MyState>>>;
+        type Error = ();
+
+        async fn next(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::Start => Ok(Some(MyState::Middle)),
+                MyState::Middle => Ok(Some(MyState::End)),
+                MyState::End => Ok(None),
+            }
+        }
+
+        async fn revert(
+            &self,
+            context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            context.unwrap().borrow_mut().push(self.clone());
+
+            match self {
+                MyState::End => Ok(Some(MyState::Middle)),
+                MyState::Middle => Ok(Some(MyState::Start)),
+                MyState::Start => Ok(None),
+            }
+        }
+    }
+
+    let reverted = Rc::new(RefCell::new(vec![]));
+
+    Runtime::new().unwrap().block_on(async {
+        let mut running = Streamline::build(MyState::Start)
+            .context(reverted.clone())
+            .run_with_shutdown();
+
+        assert_eq!(running.next().await, Some(Progress::Ok(MyState::Start)));
+
+        running.shutdown().await;
+    });
+
+    assert_eq!(*reverted.borrow(), [MyState::Middle, MyState::Start]);
+}
@@ -0,0 +1,43 @@
+use async_trait::async_trait;
+use streamline::{State, Streamline};
+
+#[test]
+fn lists_side_effecting_states() {
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Validate,
+        Charge,
+        Notify,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = ();
+        type Error = ();
+
+        async fn next(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+            Ok(None)
+        }
+
+        fn transitions(&self) -> Vec<Self> {
+            match self {
+                MyState::Validate => vec![MyState::Charge, MyState::End],
+                MyState::Charge => vec![MyState::Notify],
+                MyState::Notify => vec![MyState::End],
+                MyState::End => vec![],
+            }
+        }
+
+        fn has_side_effects(&self) -> bool {
+            matches!(self, MyState::Charge | MyState::Notify)
+        }
+    }
+
+    let streamline = Streamline::build(MyState::Validate);
+
+    assert_eq!(
+        streamline.side_effecting_states(),
+        [MyState::Charge, MyState::Notify]
+    );
+}
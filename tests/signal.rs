@@ -0,0 +1,79 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use futures::{channel::oneshot, StreamExt};
+use streamline::{Progress, RevertProgress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn cancels_on_signal() {
+    #[derive(Debug, PartialEq)]
+    struct Context;
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        Middle(String),
+        End(String),
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = Context;
+        type Error = ();
+
+        async fn next(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            let next_state = match self {
+                MyState::Start => Some(Self::Middle("hooray!".into())),
+                MyState::Middle(content) => Some(Self::End(content.into())),
+                _ => None,
+            };
+
+            Ok(next_state)
+        }
+
+        async fn revert(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            let next_state = match self {
+                MyState::End(content) => Some(Self::Middle(content.to_string())),
+                MyState::Middle(_) => Some(Self::Start),
+                _ => None,
+            };
+
+            Ok(next_state)
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let (shutdown, signal) = oneshot::channel::<()>();
+
+        let mut stream = Streamline::build(MyState::Start)
+            .context(Context)
+            .run_with_cancel(async move {
+                signal.await.ok();
+            })
+            .boxed_local();
+
+        match stream.next().await {
+            Some(Progress::Ok(state)) => assert_eq!(&state, &MyState::Start),
+            _ => panic!("incorrect start state found"),
+        };
+
+        shutdown.send(()).expect("could not send shutdown signal");
+
+        let states: Vec<_> = stream.collect().await;
+
+        assert!(!states.contains(&Progress::Ok(MyState::End("hooray!".into()))));
+
+        match states.last() {
+            Some(Progress::Revert(RevertProgress::Reverted { source, .. })) => {
+                assert_eq!(source, &None)
+            }
+            _ => panic!("incorrect terminal state found"),
+        }
+    });
+}
@@ -0,0 +1,70 @@
+use async_trait::async_trait;
+use futures::StreamExt;
+use streamline::{Progress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn snapshots_context() {
+    #[derive(Clone, Debug, PartialEq)]
+    struct Context {
+        items: Vec<u8>,
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        Middle,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = Context;
+        type Error = ();
+
+        async fn next(
+            &self,
+            context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            let context = context.ok_or(())?;
+
+            let next_state = match self {
+                MyState::Start => Some(Self::Middle),
+                MyState::Middle => Some(Self::End),
+                MyState::End => None,
+            };
+
+            context.items.push(context.items.len() as u8);
+
+            Ok(next_state)
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let snapshots: Vec<_> = Streamline::build(MyState::Start)
+            .context(Context { items: vec![] })
+            .run_with_snapshots()
+            .collect()
+            .await;
+
+        assert_eq!(
+            snapshots,
+            [
+                (
+                    Progress::Ok(MyState::Start),
+                    Some(Context { items: vec![0] })
+                ),
+                (
+                    Progress::Ok(MyState::Middle),
+                    Some(Context { items: vec![0, 1] })
+                ),
+                (
+                    Progress::Ok(MyState::End),
+                    Some(Context {
+                        items: vec![0, 1, 2]
+                    })
+                ),
+            ]
+        );
+    });
+}
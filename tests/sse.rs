@@ -0,0 +1,64 @@
+use async_trait::async_trait;
+use futures::{stream::FusedStream, StreamExt};
+use std::fmt;
+use streamline::{State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn emits_sse_frames() {
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        Middle,
+    }
+
+    impl fmt::Display for MyState {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                MyState::Start => f.write_str("starting"),
+                MyState::Middle => f.write_str("halfway"),
+            }
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = ();
+        type Error = &'static str;
+
+        async fn next(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::Start => Ok(Some(MyState::Middle)),
+                MyState::Middle => Err("out of coffee\nand patience"),
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let mut stream = Box::pin(Streamline::build(MyState::Start).run_sse());
+        let frames: Vec<_> = stream.by_ref().collect().await;
+
+        assert!(stream.is_terminated());
+
+        #[cfg(feature = "revert")]
+        assert_eq!(
+            frames,
+            [
+                "event: ok\ndata: starting\n\n",
+                "event: ok\ndata: halfway\n\n",
+                "event: reverting\ndata: halfway\n\n",
+                "event: reverted\ndata: out of coffee\ndata: and patience\n\n",
+            ]
+        );
+
+        #[cfg(not(feature = "revert"))]
+        assert_eq!(
+            frames,
+            [
+                "event: ok\ndata: starting\n\n",
+                "event: ok\ndata: halfway\n\n",
+                "event: failed\ndata: out of coffee\ndata: and patience\n\n",
+            ]
+        );
+    });
+}
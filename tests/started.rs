@@ -0,0 +1,47 @@
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::time::Instant;
+use streamline::{Progress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn emits_start_marker() {
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = ();
+        type Error = ();
+
+        async fn next(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::Start => Ok(Some(Self::End)),
+                MyState::End => Ok(None),
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let before = Instant::now();
+
+        let progress: Vec<_> = Streamline::build(MyState::Start)
+            .emit_start_marker()
+            .run()
+            .collect()
+            .await;
+
+        match progress.as_slice() {
+            [Progress::Started(started), Progress::Ok(MyState::Start), Progress::Ok(MyState::End)] => {
+                assert!(*started >= before)
+            }
+            _ => panic!("incorrect progress found"),
+        }
+    });
+}
@@ -0,0 +1,96 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use futures::StreamExt;
+use futures_timer::Delay;
+use std::time::Duration;
+use streamline::{
+    Progress, RetryPolicy, RevertProgress, State, StepPolicy, StepTimedOut, Streamline,
+};
+use tokio::runtime::Runtime;
+
+#[test]
+fn times_out_and_retries_transitions() {
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Flaky,
+        Stuck,
+        End,
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct MyError(StepTimedOut);
+
+    impl From<StepTimedOut> for MyError {
+        fn from(error: StepTimedOut) -> Self {
+            Self(error)
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = u32;
+        type Error = MyError;
+
+        async fn next(
+            &self,
+            context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            let attempts = context.unwrap();
+
+            *attempts += 1;
+
+            match self {
+                MyState::Flaky if *attempts < 3 => {
+                    Delay::new(Duration::from_secs(10)).await;
+
+                    Ok(None)
+                }
+                MyState::Flaky => Ok(Some(MyState::Stuck)),
+                MyState::Stuck => {
+                    Delay::new(Duration::from_secs(10)).await;
+
+                    Ok(Some(MyState::End))
+                }
+                MyState::End => Ok(None),
+            }
+        }
+
+        fn step_policy(&self) -> Option<StepPolicy> {
+            match self {
+                MyState::Stuck => Some(StepPolicy {
+                    timeout: Some(Duration::from_millis(20)),
+                    retries: RetryPolicy::new(0),
+                }),
+                _ => None,
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let progress: Vec<_> = Streamline::build(MyState::Flaky)
+            .context(0)
+            .step_policy(StepPolicy {
+                timeout: Some(Duration::from_millis(20)),
+                retries: RetryPolicy::new(2),
+            })
+            .run()
+            .collect()
+            .await;
+
+        assert_eq!(
+            progress,
+            [
+                Progress::Ok(MyState::Flaky),
+                Progress::Ok(MyState::Stuck),
+                Progress::Revert(RevertProgress::Reverting {
+                    step: MyState::Stuck,
+                    source: Some(MyError(StepTimedOut).into()),
+                }),
+                Progress::Revert(RevertProgress::Reverted {
+                    source: Some(MyError(StepTimedOut).into()),
+                    compensated_states: vec![],
+                }),
+            ]
+        );
+    });
+}
@@ -0,0 +1,85 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::sync::Arc;
+use streamline::{Progress, RevertProgress, State, Streamline, UnexpectedTermination};
+use tokio::runtime::Runtime;
+
+#[derive(Clone, Debug, PartialEq)]
+enum MyState {
+    Start,
+    End,
+    Broken,
+}
+
+#[derive(Debug, PartialEq)]
+enum MyError {
+    Unexpected(UnexpectedTermination),
+}
+
+impl From<UnexpectedTermination> for MyError {
+    fn from(error: UnexpectedTermination) -> Self {
+        Self::Unexpected(error)
+    }
+}
+
+#[async_trait(?Send)]
+impl State for MyState {
+    type Context = ();
+    type Error = MyError;
+
+    async fn next(
+        &self,
+        _context: Option<&mut Self::Context>,
+    ) -> Result<Option<Self>, Self::Error> {
+        match self {
+            MyState::Start => Ok(Some(Self::End)),
+            MyState::End => unreachable!("final states never run next"),
+            // forgot to transition anywhere
+            MyState::Broken => Ok(None),
+        }
+    }
+
+    fn is_final(&self) -> bool {
+        *self == MyState::End
+    }
+}
+
+#[test]
+fn reverts_on_unexpected_termination() {
+    Runtime::new().unwrap().block_on(async {
+        let states: Vec<_> = Streamline::build(MyState::Start)
+            .strict_termination()
+            .run()
+            .collect()
+            .await;
+
+        assert_eq!(
+            states,
+            [Progress::Ok(MyState::Start), Progress::Ok(MyState::End)]
+        );
+
+        let states: Vec<_> = Streamline::build(MyState::Broken)
+            .strict_termination()
+            .run()
+            .collect()
+            .await;
+
+        let source = Some(Arc::new(MyError::Unexpected(UnexpectedTermination)));
+
+        assert_eq!(
+            states,
+            [
+                Progress::Ok(MyState::Broken),
+                Progress::Revert(RevertProgress::Reverting {
+                    step: MyState::Broken,
+                    source: source.clone(),
+                }),
+                Progress::Revert(RevertProgress::Reverted {
+                    source,
+                    compensated_states: vec![],
+                }),
+            ]
+        );
+    });
+}
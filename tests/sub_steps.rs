@@ -0,0 +1,67 @@
+use async_trait::async_trait;
+use futures::StreamExt;
+use streamline::{Progress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn emits_sub_steps() {
+    #[derive(Debug, PartialEq)]
+    struct Context;
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = Context;
+        type Error = ();
+
+        async fn next(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            let next_state = match self {
+                MyState::Start => Some(Self::End),
+                MyState::End => None,
+            };
+
+            Ok(next_state)
+        }
+
+        fn sub_steps(&self) -> Vec<String> {
+            match self {
+                MyState::Start => vec!["download".into(), "unpack".into()],
+                MyState::End => vec![],
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let states: Vec<_> = Streamline::build(MyState::Start)
+            .context(Context)
+            .run()
+            .collect()
+            .await;
+
+        assert_eq!(
+            states,
+            [
+                Progress::Ok(MyState::Start),
+                Progress::SubStep {
+                    state: MyState::Start,
+                    index: 0,
+                    label: "download".into()
+                },
+                Progress::SubStep {
+                    state: MyState::Start,
+                    index: 1,
+                    label: "unpack".into()
+                },
+                Progress::Ok(MyState::End),
+            ]
+        );
+    });
+}
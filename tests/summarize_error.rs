@@ -0,0 +1,61 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use futures::StreamExt;
+use streamline::{Progress, RevertProgress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn summarizes_errors_once_reverting() {
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        End,
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum MyError {
+        Detailed(Vec<u8>),
+        Summary(usize),
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = ();
+        type Error = MyError;
+
+        async fn next(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::Start => Ok(Some(MyState::End)),
+                MyState::End => Err(MyError::Detailed(vec![0; 1024])),
+            }
+        }
+
+        async fn revert(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::End => Ok(Some(MyState::Start)),
+                MyState::Start => Ok(None),
+            }
+        }
+
+        fn summarize_error(&self, error: Self::Error) -> Self::Error {
+            match error {
+                MyError::Detailed(payload) => MyError::Summary(payload.len()),
+                summary => summary,
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let states: Vec<_> = Streamline::build(MyState::Start).run().collect().await;
+
+        let sources: Vec<_> = states
+            .iter()
+            .filter_map(|progress| match progress {
+                Progress::Revert(RevertProgress::Reverting { source, .. }) => source.as_deref(),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(sources, [&MyError::Summary(1024), &MyError::Summary(1024)]);
+    });
+}
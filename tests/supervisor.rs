@@ -0,0 +1,147 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::collections::HashMap;
+use streamline::{Progress, RevertProgress, State, Streamline, Supervisor};
+use tokio::runtime::Runtime;
+
+#[derive(Debug, PartialEq)]
+struct Context;
+
+#[derive(Clone, Debug, PartialEq)]
+enum MyState {
+    Start,
+    Middle,
+    End,
+}
+
+#[async_trait(?Send)]
+impl State for MyState {
+    type Context = Context;
+    type Error = ();
+
+    async fn next(
+        &self,
+        _context: Option<&mut Self::Context>,
+    ) -> Result<Option<Self>, Self::Error> {
+        let next_state = match self {
+            MyState::Start => Some(Self::Middle),
+            MyState::Middle => Some(Self::End),
+            MyState::End => None,
+        };
+
+        Ok(next_state)
+    }
+
+    async fn revert(
+        &self,
+        _context: Option<&mut Self::Context>,
+    ) -> Result<Option<Self>, Self::Error> {
+        let next_state = match self {
+            MyState::End => Some(Self::Middle),
+            MyState::Middle => Some(Self::Start),
+            MyState::Start => None,
+        };
+
+        Ok(next_state)
+    }
+}
+
+#[test]
+fn supervises_machines() {
+    Runtime::new().unwrap().block_on(async {
+        let mut supervisor = Supervisor::new();
+
+        let ids: Vec<_> = (0..3)
+            .map(|_| supervisor.add(Streamline::build(MyState::Start).context(Context)))
+            .collect();
+
+        let (stream, _cancel_all) = supervisor.run_all();
+
+        let mut histories = HashMap::new();
+
+        stream
+            .for_each(|(id, progress)| {
+                histories.entry(id).or_insert_with(Vec::new).push(progress);
+
+                async {}
+            })
+            .await;
+
+        assert_eq!(histories.len(), ids.len());
+
+        for id in ids {
+            assert_eq!(
+                histories[&id],
+                [
+                    Progress::Ok(MyState::Start),
+                    Progress::Ok(MyState::Middle),
+                    Progress::Ok(MyState::End),
+                ]
+            );
+        }
+    });
+}
+
+#[test]
+fn cancels_all_machines() {
+    Runtime::new().unwrap().block_on(async {
+        let mut supervisor = Supervisor::new();
+
+        for _ in 0..3 {
+            supervisor.add(Streamline::build(MyState::Start).context(Context));
+        }
+
+        let (mut stream, cancel_all) = supervisor.run_all();
+
+        assert!(stream.next().await.is_some());
+
+        cancel_all.cancel_all().expect("could not cancel machines");
+
+        let mut last_progress = HashMap::new();
+
+        while let Some((id, progress)) = stream.next().await {
+            last_progress.insert(id, progress);
+        }
+
+        assert_eq!(last_progress.len(), 3);
+
+        for progress in last_progress.values() {
+            assert!(matches!(
+                progress,
+                Progress::Revert(RevertProgress::Reverted { source: None, .. })
+            ));
+        }
+    });
+}
+
+#[test]
+fn keeps_existing_cancellation() {
+    Runtime::new().unwrap().block_on(async {
+        let mut supervisor = Supervisor::new();
+
+        let (preemptible, cancel) = Streamline::build(MyState::Start)
+            .context(Context)
+            .preemptible();
+
+        let cancelled = supervisor.add(preemptible);
+        let completed = supervisor.add(Streamline::build(MyState::Start).context(Context));
+
+        let (mut stream, _cancel_all) = supervisor.run_all();
+
+        cancel.cancel().expect("could not cancel machine");
+
+        let mut last_progress = HashMap::new();
+
+        while let Some((id, progress)) = stream.next().await {
+            last_progress.insert(id, progress);
+        }
+
+        assert!(matches!(
+            last_progress[&cancelled],
+            Progress::Revert(RevertProgress::Reverted { source: None, .. })
+        ));
+
+        assert_eq!(last_progress[&completed], Progress::Ok(MyState::End));
+    });
+}
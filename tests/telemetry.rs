@@ -0,0 +1,64 @@
+#![cfg(all(feature = "opentelemetry", feature = "revert"))]
+use async_trait::async_trait;
+use futures::StreamExt;
+use streamline::{Progress, RevertProgress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn traces_without_changing_progress() {
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        Middle,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = ();
+        type Error = &'static str;
+
+        async fn next(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::Start => Ok(Some(Self::Middle)),
+                MyState::Middle => Ok(Some(Self::End)),
+                MyState::End => Err("Something went wrong!"),
+            }
+        }
+
+        async fn revert(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::End => Ok(Some(Self::Middle)),
+                MyState::Middle => Err("Could not revert!"),
+                MyState::Start => Ok(None),
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let traced: Vec<_> = Streamline::build(MyState::Start)
+            .with_name("traced")
+            .emit_completion_marker()
+            .run()
+            .collect()
+            .await;
+
+        assert_eq!(traced.len(), 7);
+
+        assert!(matches!(
+            &traced[5],
+            Progress::Revert(RevertProgress::Failure {
+                source: Some(_),
+                error: "Could not revert!"
+            })
+        ));
+
+        assert_eq!(traced.last(), Some(&Progress::Done));
+    });
+}
@@ -0,0 +1,80 @@
+#![cfg(feature = "testing")]
+use async_trait::async_trait;
+use std::task::Poll;
+use streamline::{
+    testing::{assert_deterministic, assert_no_transition_to, assert_reaches, poll_once},
+    Progress, State, Streamline,
+};
+use tokio::runtime::Runtime;
+
+#[derive(Clone, Debug, PartialEq)]
+enum MyState {
+    Start,
+    Middle,
+    End,
+    Unreachable,
+}
+
+#[async_trait(?Send)]
+impl State for MyState {
+    type Context = ();
+    type Error = ();
+
+    async fn next(
+        &self,
+        _context: Option<&mut Self::Context>,
+    ) -> Result<Option<Self>, Self::Error> {
+        let next_state = match self {
+            MyState::Start => Some(MyState::Middle),
+            MyState::Middle => Some(MyState::End),
+            MyState::End | MyState::Unreachable => None,
+        };
+
+        Ok(next_state)
+    }
+}
+
+#[test]
+fn asserts_on_transitions() {
+    Runtime::new().unwrap().block_on(async {
+        assert_reaches(Streamline::build(MyState::Start).run(), MyState::End).await;
+        assert_no_transition_to(
+            Streamline::build(MyState::Start).run(),
+            MyState::Unreachable,
+        )
+        .await;
+    });
+}
+
+#[test]
+#[should_panic(expected = "forbidden state Middle")]
+fn panics_on_forbidden_transition() {
+    Runtime::new().unwrap().block_on(async {
+        assert_no_transition_to(Streamline::build(MyState::Start).run(), MyState::Middle).await;
+    });
+}
+
+#[test]
+fn polls_once_without_runtime() {
+    assert_eq!(
+        poll_once(Streamline::build(MyState::Start)),
+        Poll::Ready(Some(Progress::Ok(MyState::Start)))
+    );
+}
+
+#[test]
+fn asserts_determinism() {
+    Runtime::new().unwrap().block_on(async {
+        assert_deterministic(|| Streamline::build(MyState::Start)).await;
+    });
+}
+
+#[test]
+#[should_panic(expected = "Streamline is not deterministic")]
+fn panics_on_nondeterminism() {
+    let start = std::cell::Cell::new(MyState::Start);
+
+    Runtime::new().unwrap().block_on(async {
+        assert_deterministic(|| Streamline::build(start.replace(MyState::Middle))).await;
+    });
+}
@@ -0,0 +1,46 @@
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::time::Duration;
+use streamline::{Progress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn throttles_emissions() {
+    #[derive(Clone, Debug, PartialEq)]
+    struct MyState(u32);
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = ();
+        type Error = ();
+
+        async fn next(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            match self.0 {
+                100 => Ok(None),
+                count => Ok(Some(Self(count + 1))),
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let throttled: Vec<_> = Streamline::build(MyState(0))
+            .throttle(Duration::from_secs(3600))
+            .collect()
+            .await;
+
+        assert_eq!(
+            throttled,
+            [Progress::Ok(MyState(0)), Progress::Ok(MyState(100))]
+        );
+
+        let unthrottled = Streamline::build(MyState(0))
+            .throttle(Duration::from_secs(0))
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(unthrottled.len(), 101);
+    });
+}
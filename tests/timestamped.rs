@@ -0,0 +1,102 @@
+#![cfg(feature = "timestamps")]
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::time::{Duration, UNIX_EPOCH};
+use streamline::{rfc3339, Progress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[derive(Clone, Debug, PartialEq)]
+enum MyState {
+    Start,
+    Middle,
+    End,
+}
+
+#[async_trait(?Send)]
+impl State for MyState {
+    type Context = ();
+    type Error = ();
+
+    async fn next(
+        &self,
+        _context: Option<&mut Self::Context>,
+    ) -> Result<Option<Self>, Self::Error> {
+        let next_state = match self {
+            MyState::Start => Some(Self::Middle),
+            MyState::Middle => Some(Self::End),
+            MyState::End => None,
+        };
+
+        Ok(next_state)
+    }
+}
+
+#[test]
+fn timestamps_progress_as_rfc3339() {
+    Runtime::new().unwrap().block_on(async {
+        let items: Vec<_> = Streamline::build(MyState::Start)
+            .run_timestamped()
+            .collect()
+            .await;
+
+        let states: Vec<_> = items.iter().map(|(_, progress)| progress).collect();
+
+        assert_eq!(
+            states,
+            [
+                &Progress::Ok(MyState::Start),
+                &Progress::Ok(MyState::Middle),
+                &Progress::Ok(MyState::End),
+            ]
+        );
+
+        for (timestamp, _) in &items {
+            let bytes = timestamp.as_bytes();
+
+            assert_eq!(bytes.len(), "2020-03-14T15:09:26.535Z".len());
+            assert!(timestamp.ends_with('Z'));
+            assert!(timestamp.starts_with("20"));
+
+            for (index, separator) in [
+                (4, b'-'),
+                (7, b'-'),
+                (10, b'T'),
+                (13, b':'),
+                (16, b':'),
+                (19, b'.'),
+            ]
+            .iter()
+            {
+                assert_eq!(bytes[*index], *separator);
+            }
+        }
+
+        // UTC timestamps with a fixed width sort chronologically
+        assert!(items.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+    });
+}
+
+#[test]
+fn formats_known_dates() {
+    let at = |seconds, millis| {
+        rfc3339(UNIX_EPOCH + Duration::from_secs(seconds) + Duration::from_millis(millis))
+    };
+
+    assert_eq!(at(0, 0), "1970-01-01T00:00:00.000Z");
+    assert_eq!(at(951_782_400, 0), "2000-02-29T00:00:00.000Z");
+    assert_eq!(at(4_107_542_400, 0), "2100-03-01T00:00:00.000Z");
+    assert_eq!(at(1_584_198_566, 535), "2020-03-14T15:09:26.535Z");
+}
+
+#[test]
+fn formats_dates_before_the_epoch() {
+    let before = |nanos| rfc3339(UNIX_EPOCH - Duration::from_nanos(nanos));
+
+    assert_eq!(before(1), "1969-12-31T23:59:59.999Z");
+    assert_eq!(before(1_000_000), "1969-12-31T23:59:59.999Z");
+    assert_eq!(before(86_400_000_000_000), "1969-12-31T00:00:00.000Z");
+    assert_eq!(
+        rfc3339(UNIX_EPOCH - Duration::from_secs(951_782_400)),
+        "1939-11-04T00:00:00.000Z"
+    );
+}
@@ -0,0 +1,55 @@
+use async_trait::async_trait;
+use streamline::{State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn traces_forward_path() {
+    #[derive(Debug, PartialEq)]
+    struct Context {
+        healthy: bool,
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        Middle,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = Context;
+        type Error = &'static str;
+
+        async fn next(
+            &self,
+            context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            let context = context.ok_or("Missing context")?;
+
+            match self {
+                MyState::Start => Ok(Some(MyState::Middle)),
+                MyState::Middle if context.healthy => Ok(Some(MyState::End)),
+                MyState::Middle => Err("Unhealthy"),
+                MyState::End => Ok(None),
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let trace = Streamline::build(MyState::Start)
+            .trace_forward(Context { healthy: true })
+            .await;
+
+        assert_eq!(
+            trace,
+            Ok(vec![MyState::Start, MyState::Middle, MyState::End])
+        );
+
+        let trace = Streamline::build(MyState::Start)
+            .trace_forward(Context { healthy: false })
+            .await;
+
+        assert_eq!(trace, Err("Unhealthy"));
+    });
+}
@@ -0,0 +1,37 @@
+use async_trait::async_trait;
+use futures::StreamExt;
+use streamline::{Progress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn retains_recent_history() {
+    #[derive(Clone, Debug, PartialEq)]
+    struct Counter(u32);
+
+    #[async_trait(?Send)]
+    impl State for Counter {
+        type Context = ();
+        type Error = ();
+
+        async fn next(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+            match self.0 {
+                10 => Ok(None),
+                count => Ok(Some(Counter(count + 1))),
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let (stream, trace) = Streamline::build(Counter(0)).run_traced_bounded(3);
+
+        assert_eq!(stream.count().await, 11);
+        assert_eq!(
+            trace.history(),
+            [
+                Progress::Ok(Counter(8)),
+                Progress::Ok(Counter(9)),
+                Progress::Ok(Counter(10)),
+            ]
+        );
+    });
+}
@@ -1,3 +1,4 @@
+#![cfg(feature = "revert")]
 use async_trait::async_trait;
 use futures::StreamExt;
 use streamline::{Progress, State, Streamline};
@@ -0,0 +1,74 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use futures::StreamExt;
+use streamline::{Progress, RevertProgress, Streamline, Updating, UpdatingState};
+use tokio::runtime::Runtime;
+
+#[test]
+fn applies_context_updates() {
+    #[derive(Clone, Debug, PartialEq)]
+    struct Context {
+        balance: i32,
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        Deposited,
+    }
+
+    #[async_trait(?Send)]
+    impl UpdatingState for MyState {
+        type Context = Context;
+        type Error = &'static str;
+        type ContextUpdate = i32;
+
+        async fn next(
+            &self,
+            context: Option<&Self::Context>,
+        ) -> Result<(Option<Self>, Option<Self::ContextUpdate>), Self::Error> {
+            match self {
+                MyState::Start => Ok((Some(Self::Deposited), Some(100))),
+                MyState::Deposited => {
+                    assert_eq!(context.map(|context| context.balance), Some(100));
+
+                    Err("Something went wrong!")
+                }
+            }
+        }
+
+        async fn revert(
+            &self,
+            _context: Option<&Self::Context>,
+        ) -> Result<(Option<Self>, Option<Self::ContextUpdate>), Self::Error> {
+            match self {
+                MyState::Deposited => Ok((Some(Self::Start), Some(-100))),
+                MyState::Start => Ok((None, None)),
+            }
+        }
+
+        fn apply_update(context: &mut Self::Context, update: Self::ContextUpdate) {
+            context.balance += update;
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let snapshots: Vec<_> = Streamline::build(Updating(MyState::Start))
+            .context(Context { balance: 0 })
+            .run_with_snapshots()
+            .collect()
+            .await;
+
+        let balances: Vec<_> = snapshots
+            .iter()
+            .map(|(_, context)| context.as_ref().map(|context| context.balance))
+            .collect();
+
+        assert_eq!(balances, [Some(100), Some(100), Some(0), Some(0), Some(0)]);
+
+        assert!(matches!(
+            snapshots.last(),
+            Some((Progress::Revert(RevertProgress::Reverted { .. }), _))
+        ));
+    });
+}
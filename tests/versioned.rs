@@ -0,0 +1,141 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::sync::Arc;
+use streamline::{
+    ContextModified, Progress, RevertProgress, SharedContext, State, Streamline, Versioned,
+};
+use tokio::runtime::Runtime;
+
+#[derive(Clone, Debug, PartialEq)]
+enum MyState {
+    Start,
+    Middle,
+    End,
+}
+
+#[derive(Debug, PartialEq)]
+enum MyError {
+    Modified(ContextModified),
+}
+
+impl From<ContextModified> for MyError {
+    fn from(modified: ContextModified) -> Self {
+        Self::Modified(modified)
+    }
+}
+
+#[async_trait(?Send)]
+impl State for MyState {
+    type Context = Versioned<u32>;
+    type Error = MyError;
+
+    async fn next(&self, context: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+        // mutations from within the Streamline are never reported
+        **context.unwrap() += 1;
+
+        let next_state = match self {
+            MyState::Start => Some(Self::Middle),
+            MyState::Middle => Some(Self::End),
+            MyState::End => None,
+        };
+
+        Ok(next_state)
+    }
+
+    async fn revert(
+        &self,
+        _context: Option<&mut Self::Context>,
+    ) -> Result<Option<Self>, Self::Error> {
+        let next_state = match self {
+            MyState::End => Some(Self::Middle),
+            MyState::Middle => Some(Self::Start),
+            MyState::Start => None,
+        };
+
+        Ok(next_state)
+    }
+}
+
+#[test]
+fn warns_about_outside_modifications() {
+    Runtime::new().unwrap().block_on(async {
+        let shared = SharedContext::new(Versioned::new(0));
+
+        let mut states = Box::pin(
+            Streamline::build(MyState::Start)
+                .share_context(shared.clone())
+                .warn_on_context_modification()
+                .run(),
+        );
+
+        let mut progress = vec![states.next().await.unwrap()];
+
+        shared.with(|context| **context += 100).await;
+
+        progress.extend(states.collect::<Vec<_>>().await);
+
+        assert_eq!(
+            progress,
+            [
+                Progress::Ok(MyState::Start),
+                Progress::Warning {
+                    state: MyState::Middle,
+                    message: ContextModified {
+                        expected: 1,
+                        found: 2
+                    }
+                    .to_string(),
+                },
+                Progress::Ok(MyState::Middle),
+                Progress::Ok(MyState::End),
+            ]
+        );
+
+        assert_eq!(shared.with(|context| **context).await, 103);
+    });
+}
+
+#[test]
+fn reverts_on_outside_modifications() {
+    Runtime::new().unwrap().block_on(async {
+        let shared = SharedContext::new(Versioned::new(0));
+
+        let mut states = Box::pin(
+            Streamline::build(MyState::Start)
+                .share_context(shared.clone())
+                .revert_on_context_modification()
+                .run(),
+        );
+
+        let mut progress = vec![states.next().await.unwrap()];
+
+        shared.with(|context| **context += 100).await;
+
+        progress.extend(states.collect::<Vec<_>>().await);
+
+        let source = Some(Arc::new(MyError::Modified(ContextModified {
+            expected: 1,
+            found: 2,
+        })));
+
+        assert_eq!(
+            progress,
+            [
+                Progress::Ok(MyState::Start),
+                Progress::Revert(RevertProgress::Reverting {
+                    step: MyState::Middle,
+                    source: source.clone(),
+                }),
+                Progress::Revert(RevertProgress::Reverting {
+                    step: MyState::Start,
+                    source: source.clone(),
+                }),
+                Progress::Revert(RevertProgress::Reverted {
+                    source,
+                    compensated_states: vec![MyState::Middle],
+                }),
+            ]
+        );
+    });
+}
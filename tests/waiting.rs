@@ -0,0 +1,52 @@
+use async_trait::async_trait;
+use futures::StreamExt;
+use streamline::{Progress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn announces_states_waiting_on_external_resources() {
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        Throttled,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = ();
+        type Error = ();
+
+        async fn next(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::Start => Ok(Some(MyState::Throttled)),
+                MyState::Throttled => Ok(Some(MyState::End)),
+                MyState::End => Ok(None),
+            }
+        }
+
+        fn waiting_on(&self) -> Option<String> {
+            match self {
+                MyState::Throttled => Some("rate limit slot".into()),
+                _ => None,
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let states: Vec<_> = Streamline::build(MyState::Start).run().collect().await;
+
+        assert_eq!(
+            states,
+            [
+                Progress::Ok(MyState::Start),
+                Progress::Ok(MyState::Throttled),
+                Progress::Waiting {
+                    state: MyState::Throttled,
+                    reason: "rate limit slot".into(),
+                },
+                Progress::Ok(MyState::End),
+            ]
+        );
+    });
+}
@@ -0,0 +1,88 @@
+use async_trait::async_trait;
+use futures::{
+    channel::oneshot,
+    task::{waker, ArcWake},
+    Stream,
+};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+};
+use streamline::{Progress, State, Streamline};
+
+#[test]
+fn polls_with_custom_waker() {
+    #[derive(Default)]
+    struct CountingWaker(AtomicUsize);
+
+    impl ArcWake for CountingWaker {
+        fn wake_by_ref(arc_self: &Arc<Self>) {
+            arc_self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = Option<oneshot::Receiver<()>>;
+        type Error = ();
+
+        async fn next(
+            &self,
+            context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            match self {
+                MyState::Start => {
+                    let signal = context.unwrap().take().unwrap();
+
+                    signal.await.map_err(|_| ())?;
+
+                    Ok(Some(MyState::End))
+                }
+                MyState::End => Ok(None),
+            }
+        }
+    }
+
+    let (sender, receiver) = oneshot::channel();
+
+    let mut stream = Box::pin(
+        Streamline::build(MyState::Start)
+            .context(Some(receiver))
+            .run(),
+    );
+
+    let wakes = Arc::new(CountingWaker::default());
+    let waker = waker(wakes.clone());
+    let mut context = Context::from_waker(&waker);
+
+    assert!(matches!(
+        stream.as_mut().poll_next(&mut context),
+        Poll::Pending
+    ));
+    assert_eq!(wakes.0.load(Ordering::SeqCst), 0);
+
+    sender.send(()).unwrap();
+
+    assert_eq!(wakes.0.load(Ordering::SeqCst), 1);
+    assert!(matches!(
+        stream.as_mut().poll_next(&mut context),
+        Poll::Ready(Some(Progress::Ok(MyState::Start)))
+    ));
+    assert!(matches!(
+        stream.as_mut().poll_next(&mut context),
+        Poll::Ready(Some(Progress::Ok(MyState::End)))
+    ));
+    assert!(matches!(
+        stream.as_mut().poll_next(&mut context),
+        Poll::Ready(None)
+    ));
+}
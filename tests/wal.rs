@@ -0,0 +1,92 @@
+#![cfg(feature = "revert")]
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::sync::Arc;
+use streamline::{MemoryWal, Progress, RevertProgress, State, Streamline, WriteAheadLog};
+use tokio::runtime::Runtime;
+
+#[derive(Clone, Debug, PartialEq)]
+enum MyState {
+    Start,
+    Middle,
+    End,
+}
+
+#[derive(Debug, PartialEq)]
+struct MyError;
+
+#[async_trait(?Send)]
+impl State for MyState {
+    type Context = ();
+    type Error = MyError;
+
+    async fn next(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+        match self {
+            MyState::Start => Ok(Some(MyState::Middle)),
+            MyState::Middle => Ok(Some(MyState::End)),
+            MyState::End => Ok(None),
+        }
+    }
+
+    async fn revert(&self, _: Option<&mut Self::Context>) -> Result<Option<Self>, Self::Error> {
+        match self {
+            MyState::End => Ok(Some(MyState::Middle)),
+            MyState::Middle => Ok(Some(MyState::Start)),
+            MyState::Start => Ok(None),
+        }
+    }
+}
+
+#[test]
+fn records_transitions() {
+    Runtime::new().unwrap().block_on(async {
+        let wal = Arc::new(MemoryWal::new());
+
+        let states: Vec<_> = Streamline::build(MyState::Start)
+            .with_wal(wal.clone())
+            .run()
+            .collect()
+            .await;
+
+        assert_eq!(states.len(), 3);
+        assert_eq!(wal.entries(), [MyState::Middle, MyState::End]);
+
+        let resumed: Vec<_> = Streamline::from_state(wal.last().unwrap())
+            .run()
+            .collect()
+            .await;
+
+        assert_eq!(resumed, [Progress::Ok(MyState::End)]);
+    });
+}
+
+#[test]
+fn reverts_unrecorded_transitions() {
+    struct FullDisk;
+
+    #[async_trait(?Send)]
+    impl WriteAheadLog<MyState, MyError> for FullDisk {
+        async fn append(&self, state: &MyState) -> Result<(), MyError> {
+            match state {
+                MyState::End => Err(MyError),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let states: Vec<_> = Streamline::build(MyState::Start)
+            .with_wal(FullDisk)
+            .run()
+            .collect()
+            .await;
+
+        assert_eq!(
+            states[2],
+            Progress::Revert(RevertProgress::Reverting {
+                step: MyState::Middle,
+                source: Some(MyError.into()),
+            })
+        );
+    });
+}
@@ -0,0 +1,72 @@
+use async_trait::async_trait;
+use futures::StreamExt;
+use streamline::{Progress, State, Streamline};
+use tokio::runtime::Runtime;
+
+#[test]
+fn emits_warnings() {
+    #[derive(Debug, PartialEq)]
+    struct Context;
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum MyState {
+        Start,
+        Middle { retries: u8 },
+        End,
+    }
+
+    #[async_trait(?Send)]
+    impl State for MyState {
+        type Context = Context;
+        type Error = ();
+
+        async fn next(
+            &self,
+            _context: Option<&mut Self::Context>,
+        ) -> Result<Option<Self>, Self::Error> {
+            let next_state = match self {
+                MyState::Start => Some(Self::Middle { retries: 2 }),
+                MyState::Middle { .. } => Some(Self::End),
+                MyState::End => None,
+            };
+
+            Ok(next_state)
+        }
+
+        fn take_warnings(&self) -> Vec<String> {
+            match self {
+                MyState::Middle { retries } => (1..=*retries)
+                    .map(|retry| format!("retry #{}", retry))
+                    .collect(),
+                _ => vec![],
+            }
+        }
+    }
+
+    Runtime::new().unwrap().block_on(async {
+        let states: Vec<_> = Streamline::build(MyState::Start)
+            .context(Context)
+            .run()
+            .collect()
+            .await;
+
+        let middle = MyState::Middle { retries: 2 };
+
+        assert_eq!(
+            states,
+            [
+                Progress::Ok(MyState::Start),
+                Progress::Warning {
+                    state: middle.clone(),
+                    message: "retry #1".into()
+                },
+                Progress::Warning {
+                    state: middle.clone(),
+                    message: "retry #2".into()
+                },
+                Progress::Ok(middle),
+                Progress::Ok(MyState::End),
+            ]
+        );
+    });
+}